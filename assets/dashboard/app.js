@@ -0,0 +1,109 @@
+const STATUS_LABELS = {
+  active: "Active",
+  eligible: "Eligible",
+  not_registered: "Not registered",
+  ineligible: "Ineligible",
+  unknown: "Unknown",
+};
+
+const sol = (value) => `${Math.round(value).toLocaleString()} SOL`;
+const usd = (value) => `$${Math.round(value).toLocaleString()}`;
+
+const $ = (id) => document.getElementById(id);
+
+function showMessage(text, isError = false) {
+  $("message").textContent = text;
+  $("message").className = isError ? "error" : "";
+}
+
+async function loadPrograms() {
+  const response = await fetch("/api/programs");
+  if (!response.ok) return;
+  for (const program of await response.json()) {
+    const option = document.createElement("option");
+    option.value = program.name;
+    option.textContent = program.display_name;
+    $("program").appendChild(option);
+  }
+}
+
+function renderScan(result) {
+  const { summary } = result;
+  $("total-current").textContent = sol(summary.total_current_sol);
+  $("total-potential").textContent = sol(summary.total_potential_sol);
+  $("missed-sol").textContent = `${sol(summary.missed_revenue_sol)}/yr`;
+  $("missed-usd").textContent = `${usd(summary.missed_revenue_usd)}/yr`;
+
+  const rows = $("program-rows");
+  rows.replaceChildren();
+  for (const program of result.programs) {
+    const row = rows.insertRow();
+    row.insertCell().textContent = program.display_name;
+    const status = row.insertCell();
+    status.textContent = STATUS_LABELS[program.status] ?? program.status;
+    status.className = `status-${program.status}`;
+    for (const value of [program.current_stake_sol, program.potential_stake_sol, program.gap_sol]) {
+      const cell = row.insertCell();
+      cell.textContent = sol(value);
+      cell.className = "num";
+    }
+  }
+
+  const actions = $("action-items");
+  actions.replaceChildren();
+  for (const action of summary.action_items) {
+    const item = document.createElement("li");
+    item.textContent = `${action.action} (+${sol(action.potential_gain_sol)}, ${action.difficulty}) `;
+    if (action.url) {
+      const link = document.createElement("a");
+      link.href = action.url;
+      link.target = "_blank";
+      link.rel = "noopener";
+      link.textContent = "register";
+      item.appendChild(link);
+    }
+    actions.appendChild(item);
+  }
+
+  $("summary").hidden = false;
+  $("programs").hidden = false;
+  $("actions").hidden = summary.action_items.length === 0;
+  $("scanned-at").textContent = `Scanned ${new Date(result.scanned_at).toLocaleString()}`;
+}
+
+async function scan(event) {
+  event.preventDefault();
+  const params = new URLSearchParams({ validator: $("validator").value.trim() });
+  if ($("program").value) params.set("program", $("program").value);
+
+  const button = event.target.querySelector("button");
+  button.disabled = true;
+  showMessage("Scanning...");
+
+  try {
+    const response = await fetch(`/api/scan?${params}`);
+    const body = await response.json();
+    if (!response.ok) {
+      showMessage(body.error ?? `Scan failed (${response.status})`, true);
+      return;
+    }
+    showMessage("");
+    renderScan(body);
+    history.replaceState(null, "", `?${params}`);
+  } catch (err) {
+    showMessage(`Scan failed: ${err.message}`, true);
+  } finally {
+    button.disabled = false;
+  }
+}
+
+$("scan-form").addEventListener("submit", scan);
+
+loadPrograms().then(() => {
+  const params = new URLSearchParams(location.search);
+  if (params.has("validator")) {
+    $("validator").value = params.get("validator");
+    $("program").value = params.get("program") ?? "";
+    $("scan-form").requestSubmit();
+  }
+});
@@ -1,69 +1,252 @@
 //! REST API server
 
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use axum::{
-    extract::Query,
-    http::StatusCode,
-    response::Json,
-    routing::get,
+    body::Body,
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        ConnectInfo, Query, Request, State,
+    },
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
     Router,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::{
+    cors::{Any, CorsLayer},
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::{DefaultOnResponse, TraceLayer},
+    LatencyUnit,
+};
+use tracing::Level;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{
+    cache::TtlCache, fixtures::Upstream, price::PriceSource, ratelimit::RateLimiter, scanners, types::*,
+};
+
+/// Upper bound on validators in one bulk scan request
+const MAX_BULK_VALIDATORS: usize = 100;
+
+const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Largest error body that gets a request ID spliced in
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
 
-use crate::{scanners, types::*};
+/// Recent scans keyed by (validator, program)
+type ScanCache = TtlCache<(String, Option<String>), ScanResult>;
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone)]
+struct ApiState {
+    price_source: PriceSource,
+    upstream: Upstream,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    scan_cache: Option<Arc<ScanCache>>,
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(health, programs, scan, scan_bulk),
+    info(title = "Delegation Oracle API"),
+)]
+struct ApiDoc;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ScanQuery {
+    /// Validator vote account pubkey
     validator: String,
+    /// Restrict the scan to one program
+    program: Option<String>,
+    /// Skip the response cache and scan again
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema, JsonSchema)]
+pub(crate) struct BulkScanRequest {
+    /// Validator vote account pubkeys
+    validators: Vec<String>,
+    /// Restrict the scan to one program
     program: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct HealthResponse {
+#[derive(Debug, Serialize, ToSchema, JsonSchema)]
+pub(crate) struct HealthResponse {
     ok: bool,
     version: &'static str,
 }
 
-#[derive(Debug, Serialize)]
-struct ProgramInfo {
+#[derive(Debug, Serialize, ToSchema, JsonSchema)]
+pub(crate) struct ProgramInfo {
     name: &'static str,
     display_name: &'static str,
     description: &'static str,
     registration_url: &'static str,
 }
 
-#[derive(Debug, Serialize)]
-struct ErrorResponse {
+#[derive(Debug, Serialize, ToSchema, JsonSchema)]
+pub(crate) struct ErrorResponse {
     error: String,
+    /// Added by the request ID middleware, mirrors the x-request-id header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
 }
 
-pub async fn serve(host: &str, port: u16) -> anyhow::Result<()> {
+pub async fn serve(
+    host: &str,
+    port: u16,
+    price_source: PriceSource,
+    upstream: Upstream,
+    rate_limiter: Option<RateLimiter>,
+    scan_cache_ttl: Duration,
+) -> anyhow::Result<()> {
+    let state = ApiState {
+        price_source,
+        upstream,
+        rate_limiter: rate_limiter.map(Arc::new),
+        scan_cache: (!scan_cache_ttl.is_zero()).then(|| Arc::new(TtlCache::new(scan_cache_ttl))),
+    };
+    
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([REQUEST_ID_HEADER]);
     
-    let app = Router::new()
-        .route("/", get(index))
-        .route("/api/health", get(health))
+    let trace = TraceLayer::new_for_http()
+        .make_span_with(|request: &Request| {
+            let request_id = request
+                .headers()
+                .get(&REQUEST_ID_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("-");
+            let validator = request
+                .uri()
+                .query()
+                .and_then(|q| q.split('&').find_map(|pair| pair.strip_prefix("validator=")))
+                .unwrap_or("-");
+            tracing::info_span!(
+                "request",
+                request_id,
+                method = %request.method(),
+                path = request.uri().path(),
+                validator,
+            )
+        })
+        .on_response(
+            DefaultOnResponse::new()
+                .level(Level::INFO)
+                .latency_unit(LatencyUnit::Millis),
+        );
+    
+    let app = Router::new();
+    #[cfg(feature = "dashboard")]
+    let app = app.merge(crate::dashboard::router());
+    #[cfg(not(feature = "dashboard"))]
+    let app = app.route("/", get(index));
+    
+    // Health checks, docs and static assets are cheap and polled, so only
+    // the data endpoints count against the rate limit
+    let limited = Router::new()
         .route("/api/programs", get(programs))
         .route("/api/scan", get(scan))
-        .layer(cors);
+        .route("/api/scan/bulk", post(scan_bulk))
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit));
+    
+    let app = app
+        .route("/api/health", get(health))
+        .merge(limited)
+        .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()))
+        .layer(middleware::from_fn(tag_error_with_request_id))
+        .layer(cors)
+        .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+        .layer(trace)
+        .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
+        .with_state(state);
     
     let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
     tracing::info!("Starting Delegation Oracle API on http://{}", addr);
     
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
     
     Ok(())
 }
 
+async fn rate_limit(
+    State(state): State<ApiState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(limiter) = &state.rate_limiter {
+        if let Err(retry_after) = limiter.check(addr.ip()) {
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ErrorResponse {
+                    error: "Rate limit exceeded".to_string(),
+                    request_id: None,
+                }),
+            )
+                .into_response();
+            response.headers_mut().insert(
+                header::RETRY_AFTER,
+                HeaderValue::from(retry_after.as_secs().max(1)),
+            );
+            return response;
+        }
+    }
+    
+    next.run(request).await
+}
+
+/// Copy the request ID into JSON error bodies so clients can quote it in bug reports
+async fn tag_error_with_request_id(request: Request, next: Next) -> Response {
+    let request_id = request.headers().get(&REQUEST_ID_HEADER).cloned();
+    let response = next.run(request).await;
+    
+    let status = response.status();
+    let Some(request_id) = request_id.and_then(|v| v.to_str().map(String::from).ok()) else {
+        return response;
+    };
+    if !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+    
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut error)) => {
+            error.insert("request_id".to_string(), request_id.into());
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(serde_json::Value::Object(error).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(not(feature = "dashboard"))]
 async fn index() -> &'static str {
     "Delegation Oracle API - https://github.com/jque-designs/delegation-oracle"
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    responses((status = 200, description = "Server is up", body = HealthResponse)),
+)]
 async fn health() -> Json<HealthResponse> {
     Json(HealthResponse {
         ok: true,
@@ -71,6 +254,11 @@ async fn health() -> Json<HealthResponse> {
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/programs",
+    responses((status = 200, description = "Supported programs", body = [ProgramInfo])),
+)]
 async fn programs() -> Json<Vec<ProgramInfo>> {
     Json(vec![
         ProgramInfo {
@@ -106,26 +294,106 @@ async fn programs() -> Json<Vec<ProgramInfo>> {
     ])
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/scan",
+    params(ScanQuery),
+    responses(
+        (status = 200, description = "Scan result", body = ScanResult),
+        (status = 400, description = "Missing or invalid query parameters", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+        (status = 500, description = "Scan failed", body = ErrorResponse),
+    ),
+)]
 async fn scan(
-    Query(query): Query<ScanQuery>,
-) -> Result<Json<ScanResult>, (StatusCode, Json<ErrorResponse>)> {
-    // Validate pubkey format (basic check)
-    if query.validator.len() < 32 || query.validator.len() > 44 {
-        return Err((
+    State(state): State<ApiState>,
+    query: Result<Query<ScanQuery>, QueryRejection>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let Query(query) = query.map_err(|e| error_response(e.status(), e.body_text()))?;
+    check_pubkey(&query.validator)?;
+    
+    let key = (query.validator.clone(), query.program.clone());
+    if !query.refresh {
+        if let Some((result, remaining)) = state.scan_cache.as_ref().and_then(|c| c.get(&key)) {
+            return Ok(cached_response(result, remaining));
+        }
+    }
+    
+    let result = scanners::scan_validator(&query.validator, query.program.as_deref(), &state.price_source, &state.upstream)
+        .await
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    
+    // Degraded results are served but never cached
+    let max_age = match &state.scan_cache {
+        Some(_) if result.is_partial() => Duration::ZERO,
+        Some(cache) => {
+            cache.insert(key, result.clone());
+            cache.ttl()
+        }
+        None => Duration::ZERO,
+    };
+    Ok(cached_response(result, max_age))
+}
+
+fn cached_response(result: ScanResult, max_age: Duration) -> Response {
+    let cache_control = if max_age.is_zero() {
+        "no-store".to_string()
+    } else {
+        format!("public, max-age={}", max_age.as_secs())
+    };
+    ([(header::CACHE_CONTROL, cache_control)], Json(result)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/scan/bulk",
+    request_body = BulkScanRequest,
+    responses(
+        (status = 200, description = "One scan result per validator, in request order", body = [ScanResult]),
+        (status = 400, description = "Malformed body, invalid validator pubkey or too many validators", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+        (status = 500, description = "Scan failed", body = ErrorResponse),
+    ),
+)]
+async fn scan_bulk(
+    State(state): State<ApiState>,
+    request: Result<Json<BulkScanRequest>, JsonRejection>,
+) -> Result<Json<Vec<ScanResult>>, (StatusCode, Json<ErrorResponse>)> {
+    let Json(request) = request.map_err(|e| error_response(e.status(), e.body_text()))?;
+    if request.validators.len() > MAX_BULK_VALIDATORS {
+        return Err(error_response(
             StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Invalid validator pubkey".to_string(),
-            }),
+            format!("At most {} validators per request", MAX_BULK_VALIDATORS),
         ));
     }
+    for validator in &request.validators {
+        check_pubkey(validator)?;
+    }
     
-    match scanners::scan_validator(&query.validator, query.program.as_deref()).await {
-        Ok(result) => Ok(Json(result)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )),
+    let results = scanners::scan_validators(
+        &request.validators,
+        request.program.as_deref(),
+        &state.price_source,
+        &state.upstream,
+    )
+    .await;
+    match results {
+        Ok(results) => Ok(Json(results)),
+        Err(e) => Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
     }
 }
+
+/// Validate pubkey format (basic check)
+fn check_pubkey(validator: &str) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if validator.len() < 32 || validator.len() > 44 {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid validator pubkey: {}", validator),
+        ));
+    }
+    Ok(())
+}
+
+fn error_response(status: StatusCode, error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (status, Json(ErrorResponse { error, request_id: None }))
+}
@@ -0,0 +1,45 @@
+//! In-memory TTL cache for API responses
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Sweep expired entries once the cache grows past this size
+const SWEEP_THRESHOLD: usize = 1_000;
+
+#[derive(Debug)]
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cached value and its remaining lifetime, if still fresh
+    pub fn get(&self, key: &K) -> Option<(V, Duration)> {
+        let entries = self.entries.lock().unwrap();
+        let (stored_at, value) = entries.get(key)?;
+        let remaining = self.ttl.checked_sub(stored_at.elapsed())?;
+        Some((value.clone(), remaining))
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= SWEEP_THRESHOLD {
+            let ttl = self.ttl;
+            entries.retain(|_, (stored_at, _)| stored_at.elapsed() < ttl);
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
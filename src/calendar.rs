@@ -0,0 +1,118 @@
+//! Next delegation decision point per program, counted from the current epoch
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{clock::DEFAULT_MS_PER_SLOT, epoch_info::EpochInfo};
+
+use crate::fixtures::Upstream;
+
+pub const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// Appended to the RPC URL to name the epoch info fixture
+pub(crate) const EPOCH_INFO_METHOD: &str = "#getEpochInfo";
+
+/// How often a program revisits its delegations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cadence {
+    /// At every epoch boundary
+    EveryEpoch,
+    /// Once per cycle of this many epochs
+    Cycle(u64),
+    /// No schedule we can rely on
+    Unpublished,
+}
+
+/// Rebalance cadence by program slug
+pub const SCHEDULES: [(&str, Cadence); 5] = [
+    ("marinade", Cadence::EveryEpoch),
+    ("jito", Cadence::Cycle(10)),
+    ("blaze", Cadence::Unpublished),
+    ("sanctum", Cadence::Unpublished),
+    ("sfdp", Cadence::Unpublished),
+];
+
+/// Current epoch from `rpc_url`, or from fixtures when recording, replaying or in demo mode
+pub async fn epoch_info(rpc_url: &str, upstream: &Upstream) -> Result<EpochInfo> {
+    let timeout = upstream.request_timeout(Duration::from_secs(10));
+    let fetch = async {
+        RpcClient::new_with_timeout(rpc_url.to_string(), timeout)
+            .get_epoch_info()
+            .await
+            .with_context(|| format!("fetching epoch info from {}", rpc_url))
+    };
+    upstream
+        .load_json(&format!("{}{}", rpc_url, EPOCH_INFO_METHOD), fetch)
+        .await
+}
+
+/// Estimated time to the end of the current epoch at the target slot time
+pub fn until_epoch_end(info: &EpochInfo) -> Duration {
+    let slots_left = info.slots_in_epoch.saturating_sub(info.slot_index);
+    Duration::from_millis(slots_left * DEFAULT_MS_PER_SLOT)
+}
+
+/// Estimated length of a whole epoch at the target slot time
+pub fn epoch_length(info: &EpochInfo) -> Duration {
+    Duration::from_millis(info.slots_in_epoch * DEFAULT_MS_PER_SLOT)
+}
+
+pub fn print_calendar(info: &EpochInfo) {
+    let hours_left = until_epoch_end(info).as_secs_f64() / 3600.0;
+    let epoch_hours = epoch_length(info).as_secs_f64() / 3600.0;
+    println!(
+        "\nEpoch {} is {:.0}% through; epoch {} starts in ~{:.1}h (at {}ms/slot)\n",
+        info.epoch,
+        info.slot_index as f64 / info.slots_in_epoch as f64 * 100.0,
+        info.epoch + 1,
+        hours_left,
+        DEFAULT_MS_PER_SLOT
+    );
+
+    for (program, cadence) in SCHEDULES {
+        let next = match cadence {
+            Cadence::EveryEpoch => format!("epoch {} boundary, in ~{:.1}h", info.epoch + 1, hours_left),
+            // The cycle start is not tracked, so the worst case is a cycle that
+            // begins at the end of the last epoch of this window
+            Cadence::Cycle(epochs) => format!(
+                "next {}-epoch cycle, within ~{:.0}h (cycle start not tracked)",
+                epochs,
+                hours_left + epochs.saturating_sub(1) as f64 * epoch_hours
+            ),
+            Cadence::Unpublished => "no published schedule".to_string(),
+        };
+        println!("  {:<10} {}", program, next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(slot_index: u64) -> EpochInfo {
+        EpochInfo {
+            epoch: 800,
+            slot_index,
+            slots_in_epoch: 432_000,
+            absolute_slot: 800 * 432_000 + slot_index,
+            block_height: 0,
+            transaction_count: None,
+        }
+    }
+
+    #[test]
+    fn epoch_length_at_target_slot_time() {
+        // 432,000 slots at 400ms is two days
+        assert_eq!(epoch_length(&info(0)), Duration::from_secs(48 * 3600));
+    }
+
+    #[test]
+    fn time_left_counts_the_remaining_slots() {
+        assert_eq!(until_epoch_end(&info(0)), Duration::from_secs(48 * 3600));
+        assert_eq!(until_epoch_end(&info(216_000)), Duration::from_secs(24 * 3600));
+        assert_eq!(until_epoch_end(&info(431_999)), Duration::from_millis(400));
+        // A slot index past the end (stale or odd RPC data) never goes negative
+        assert_eq!(until_epoch_end(&info(500_000)), Duration::ZERO);
+    }
+}
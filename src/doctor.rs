@@ -0,0 +1,162 @@
+//! Self-diagnosis: checks every upstream the scan depends on
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::calendar;
+use crate::fixtures::Upstream;
+use crate::price::{self, PriceSource};
+use crate::scanners::{ESTIMATED_PROGRAMS, MARINADE_VALIDATORS_URL};
+
+/// Clock skew beyond this makes scan timestamps misleading
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub name: String,
+    pub level: Level,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, level: Level, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            level,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Run every check; network checks run concurrently and always go to the
+/// network, since a recorded or demo payload says nothing about the upstream
+pub async fn run(price_source: &PriceSource, rpc_url: &str, upstream: &Upstream) -> Vec<Check> {
+    let upstream = &upstream.to_live();
+    let (price, marinade, rpc) = tokio::join!(
+        check_price(price_source, upstream),
+        check_marinade(upstream),
+        check_rpc(rpc_url, upstream)
+    );
+
+    let mut checks = vec![price];
+    checks.extend(marinade);
+    checks.push(rpc);
+    for program in ESTIMATED_PROGRAMS {
+        checks.push(Check::new(
+            format!("program {}", program),
+            Level::Warn,
+            "no live data source, status is always unknown and stake is an estimate",
+        ));
+    }
+    checks
+}
+
+pub fn print_report(checks: &[Check]) {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in checks {
+        let label = match check.level {
+            Level::Pass => "PASS",
+            Level::Warn => "WARN",
+            Level::Fail => "FAIL",
+        };
+        println!("[{}] {:<w$}  {}", label, check.name, check.detail, w = width);
+    }
+
+    let count = |level| checks.iter().filter(|c| c.level == level).count();
+    println!(
+        "\n{} passed, {} warnings, {} failed",
+        count(Level::Pass),
+        count(Level::Warn),
+        count(Level::Fail)
+    );
+}
+
+async fn check_price(source: &PriceSource, upstream: &Upstream) -> Check {
+    let name = format!("price source ({})", source.name());
+    if let PriceSource::Fixed(price) = source {
+        return Check::new(name, Level::Pass, format!("fixed at ${:.2}, no network needed", price));
+    }
+    match price::fetch_price(source, upstream).await {
+        Ok(price) if price > 0.0 => Check::new(name, Level::Pass, format!("${:.2}", price)),
+        Ok(price) => Check::new(name, Level::Fail, format!("implausible price ${:.2}", price)),
+        Err(e) => Check::new(
+            name,
+            Level::Fail,
+            format!("{} (USD figures fall back to ${:.0})", e, price::FALLBACK_SOL_PRICE_USD),
+        ),
+    }
+}
+
+/// Marinade reachability and parseability, plus clock skew from its Date header
+async fn check_marinade(upstream: &Upstream) -> Vec<Check> {
+    let name = "program marinade";
+    let resp = match reqwest::Client::new()
+        .get(MARINADE_VALIDATORS_URL)
+        .timeout(upstream.request_timeout(Duration::from_secs(10)))
+        .send()
+        .await
+    {
+        Ok(resp) => resp,
+        Err(e) => return vec![Check::new(name, Level::Fail, format!("unreachable: {}", e))],
+    };
+
+    let skew = check_clock_skew(resp.headers().get(reqwest::header::DATE));
+
+    let status = resp.status();
+    let program = if !status.is_success() {
+        Check::new(name, Level::Fail, format!("HTTP {}, scans report unknown", status))
+    } else {
+        match resp.json::<Vec<serde_json::Value>>().await {
+            Ok(validators) if validators.iter().any(|v| v.get("vote_account").is_some()) => {
+                Check::new(name, Level::Pass, format!("{} validators", validators.len()))
+            }
+            Ok(_) => Check::new(name, Level::Fail, "response has no vote_account fields"),
+            Err(e) => Check::new(name, Level::Fail, format!("unparseable response: {}", e)),
+        }
+    };
+
+    vec![program, skew]
+}
+
+/// RPC reachability, via the epoch fetch `calendar` depends on
+async fn check_rpc(rpc_url: &str, upstream: &Upstream) -> Check {
+    let name = "solana rpc";
+    match calendar::epoch_info(rpc_url, upstream).await {
+        Ok(info) => Check::new(
+            name,
+            Level::Pass,
+            format!("epoch {}, slot {}/{}", info.epoch, info.slot_index, info.slots_in_epoch),
+        ),
+        // The RPC client nests the same cause several times over; the root says it
+        Err(e) => Check::new(
+            name,
+            Level::Fail,
+            format!("{} unreachable: {}, calendar is unavailable", rpc_url, e.root_cause()),
+        ),
+    }
+}
+
+fn check_clock_skew(date: Option<&reqwest::header::HeaderValue>) -> Check {
+    let name = "clock skew";
+    let Some(server_time) = date
+        .and_then(|d| d.to_str().ok())
+        .and_then(|d| DateTime::parse_from_rfc2822(d).ok())
+    else {
+        return Check::new(name, Level::Warn, "no Date header to compare against");
+    };
+
+    let skew = Utc::now().signed_duration_since(server_time).num_seconds();
+    if skew.unsigned_abs() > MAX_CLOCK_SKEW.as_secs() {
+        Check::new(name, Level::Warn, format!("local clock is {:+}s off the Marinade API", skew))
+    } else {
+        Check::new(name, Level::Pass, format!("{:+}s", skew))
+    }
+}
@@ -0,0 +1,159 @@
+//! `--fail-on` conditions that turn scan results into a non-zero exit code
+
+use std::str::FromStr;
+
+use crate::scanners::PROGRAM_NAMES;
+use crate::types::*;
+
+/// Exit code used when a `--fail-on` condition matches (1 stays reserved for errors)
+pub const EXIT_CODE: i32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FailOn {
+    /// A program reports the validator as ineligible
+    Ineligible(Vec<String>),
+    /// A program isn't actively delegating to the validator
+    NotActive(Vec<String>),
+    /// A program's status couldn't be determined
+    Unknown(Vec<String>),
+}
+
+impl FromStr for FailOn {
+    type Err = String;
+
+    /// Parses `kind` or `kind:program,program`; no list means any program
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, programs) = match s.split_once(':') {
+            Some((kind, list)) if list.trim().is_empty() => {
+                return Err(format!("no programs after '{}:' (drop the ':' to match any program)", kind));
+            }
+            Some((kind, list)) => (kind, list.split(',').map(|p| p.trim().to_string()).collect()),
+            None => (s, Vec::new()),
+        };
+
+        if let Some(p) = programs.iter().find(|p| !PROGRAM_NAMES.contains(&p.as_str())) {
+            return Err(format!("unknown program '{}' (expected one of: {})", p, PROGRAM_NAMES.join(", ")));
+        }
+
+        match kind {
+            "ineligible" => Ok(FailOn::Ineligible(programs)),
+            "not-active" => Ok(FailOn::NotActive(programs)),
+            "unknown" => Ok(FailOn::Unknown(programs)),
+            other => Err(format!(
+                "unknown condition '{}' (expected ineligible, not-active or unknown)",
+                other
+            )),
+        }
+    }
+}
+
+impl FailOn {
+    /// Describe every program in `result` that trips this condition
+    pub fn check(&self, result: &ScanResult) -> Vec<String> {
+        let (programs, label) = match self {
+            FailOn::Ineligible(p) => (p, "ineligible"),
+            FailOn::NotActive(p) => (p, "not active"),
+            FailOn::Unknown(p) => (p, "status unknown"),
+        };
+
+        result
+            .programs
+            .iter()
+            .filter(|p| programs.is_empty() || programs.contains(&p.name))
+            .filter(|p| match self {
+                FailOn::Ineligible(_) => p.status == RegistrationStatus::Ineligible,
+                FailOn::NotActive(_) => p.status != RegistrationStatus::Active,
+                FailOn::Unknown(_) => p.status == RegistrationStatus::Unknown,
+            })
+            .map(|p| format!("{}: {}", p.name, label))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(statuses: &[(&str, RegistrationStatus)]) -> ScanResult {
+        ScanResult {
+            validator: "validator".to_string(),
+            scanned_at: chrono::Utc::now(),
+            programs: statuses
+                .iter()
+                .map(|(name, status)| ProgramStatus::new(name, name).with_status(status.clone()))
+                .collect(),
+            demo: false,
+            summary: ScanSummary {
+                total_current_sol: 0.0,
+                total_potential_sol: 0.0,
+                missed_revenue_sol: 0.0,
+                missed_revenue_usd: 0.0,
+                sol_price_usd: 0.0,
+                price_is_fallback: false,
+                action_items: Vec::new(),
+                milestones: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn parses_a_bare_kind_as_any_program() {
+        assert_eq!("ineligible".parse(), Ok(FailOn::Ineligible(vec![])));
+        assert_eq!("not-active".parse(), Ok(FailOn::NotActive(vec![])));
+        assert_eq!("unknown".parse(), Ok(FailOn::Unknown(vec![])));
+    }
+
+    #[test]
+    fn parses_a_program_list() {
+        assert_eq!(
+            "not-active:marinade, jito".parse(),
+            Ok(FailOn::NotActive(vec!["marinade".to_string(), "jito".to_string()]))
+        );
+    }
+
+    #[test]
+    fn rejects_an_empty_program_list() {
+        let err = "ineligible:".parse::<FailOn>().unwrap_err();
+        assert!(err.contains("no programs after 'ineligible:'"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_an_unknown_program() {
+        let err = "ineligible:marinade,lido".parse::<FailOn>().unwrap_err();
+        assert!(err.contains("unknown program 'lido'"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_an_unknown_kind() {
+        let err = "delinquent".parse::<FailOn>().unwrap_err();
+        assert!(err.contains("unknown condition 'delinquent'"), "{}", err);
+    }
+
+    #[test]
+    fn check_reports_matching_programs() {
+        let result = result(&[
+            ("marinade", RegistrationStatus::Active),
+            ("jito", RegistrationStatus::Ineligible),
+            ("blaze", RegistrationStatus::Unknown),
+            ("sanctum", RegistrationStatus::NotRegistered),
+        ]);
+
+        assert_eq!(FailOn::Ineligible(vec![]).check(&result), ["jito: ineligible"]);
+        assert_eq!(FailOn::Unknown(vec![]).check(&result), ["blaze: status unknown"]);
+        assert_eq!(
+            FailOn::NotActive(vec![]).check(&result),
+            ["jito: not active", "blaze: not active", "sanctum: not active"]
+        );
+    }
+
+    #[test]
+    fn check_only_looks_at_listed_programs() {
+        let result = result(&[
+            ("marinade", RegistrationStatus::Active),
+            ("jito", RegistrationStatus::Unknown),
+        ]);
+
+        assert!(FailOn::NotActive(vec!["marinade".to_string()]).check(&result).is_empty());
+        assert_eq!(FailOn::NotActive(vec!["jito".to_string()]).check(&result), ["jito: not active"]);
+    }
+}
@@ -0,0 +1,286 @@
+//! Record and replay of upstream HTTP payloads (`--record` / `--replay` / `--demo`)
+//!
+//! Every upstream JSON fetch goes through [`Upstream::get_json`]. Recording saves the
+//! raw response body before it is parsed, so a payload that breaks parsing can
+//! be replayed exactly. Fixtures are named after the URL they came from.
+//! Payloads that are not a plain GET (JSON-RPC) go through [`Upstream::load_json`].
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::calendar::EPOCH_INFO_METHOD;
+use crate::price::{PriceCache, COINGECKO_URL, PYTH_URL};
+use crate::scanners::MARINADE_VALIDATORS_URL;
+
+/// Validator that is active with Marinade in the demo data
+pub const DEMO_VALIDATOR: &str = "DemoActiveVa1idatorVoteAccount1111111111111";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    /// Fetch live and save each payload into the directory
+    Record(PathBuf),
+    /// Serve every fetch from the directory, never touching the network
+    Replay(PathBuf),
+    /// Serve built-in sample payloads, never touching the network
+    Demo,
+}
+
+/// How upstream fetches are served; each oracle, command or server holds its own
+#[derive(Debug, Clone, Default)]
+pub struct Upstream {
+    mode: Option<Mode>,
+    deadline: Option<Instant>,
+    price_cache: Arc<PriceCache>,
+}
+
+impl Upstream {
+    /// Fetch everything from the network
+    pub fn live() -> Self {
+        Self::default()
+    }
+
+    pub fn new(mode: Mode) -> Result<Self> {
+        if let Mode::Record(dir) = &mode {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("creating fixture directory {}", dir.display()))?;
+        }
+        Ok(Self {
+            mode: Some(mode),
+            ..Self::default()
+        })
+    }
+
+    /// Cap every later request at the time left until `timeout` from now, so a
+    /// stuck endpoint fails with an error naming it instead of hanging
+    pub fn with_deadline(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// Timeout for one request: `default`, cut short by the deadline if one is set
+    pub fn request_timeout(&self, default: Duration) -> Duration {
+        match self.deadline {
+            Some(deadline) => default.min(deadline.saturating_duration_since(Instant::now())),
+            None => default,
+        }
+    }
+
+    /// Same deadline, but every fetch goes to the network
+    pub fn to_live(&self) -> Self {
+        Self {
+            deadline: self.deadline,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn price_cache(&self) -> &PriceCache {
+        &self.price_cache
+    }
+
+    pub fn mode(&self) -> Option<&Mode> {
+        self.mode.as_ref()
+    }
+
+    /// Plain network fetches, neither recorded nor replayed
+    pub fn is_live(&self) -> bool {
+        self.mode.is_none()
+    }
+
+    /// GET `url` and parse the body as JSON, honouring the record/replay mode
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str, timeout: Duration) -> Result<T> {
+        let body = match &self.mode {
+            Some(Mode::Replay(dir)) => {
+                let path = fixture_path(dir, url);
+                std::fs::read(&path)
+                    .with_context(|| format!("no fixture for {} at {}", url, path.display()))?
+            }
+            Some(Mode::Record(dir)) => {
+                let body = fetch(url, timeout).await?;
+                let path = fixture_path(dir, url);
+                std::fs::write(&path, &body)
+                    .with_context(|| format!("recording fixture {}", path.display()))?;
+                body
+            }
+            Some(Mode::Demo) => demo_payload(url)
+                .with_context(|| format!("no demo data for {}", url))?
+                .as_bytes()
+                .to_vec(),
+            None => fetch(url, timeout).await?,
+        };
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Like [`Upstream::get_json`] for payloads `fetch` gets some other way;
+    /// `url` only names the fixture. Recording saves the parsed value.
+    pub async fn load_json<T, F>(&self, url: &str, fetch: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = Result<T>>,
+    {
+        match &self.mode {
+            Some(Mode::Replay(_) | Mode::Demo) => self.get_json(url, Duration::ZERO).await,
+            Some(Mode::Record(dir)) => {
+                let value = fetch.await?;
+                let path = fixture_path(dir, url);
+                std::fs::write(&path, serde_json::to_vec(&value)?)
+                    .with_context(|| format!("recording fixture {}", path.display()))?;
+                Ok(value)
+            }
+            None => fetch.await,
+        }
+    }
+}
+
+async fn fetch(url: &str, timeout: Duration) -> Result<Vec<u8>> {
+    Ok(reqwest::Client::new()
+        .get(url)
+        .timeout(timeout)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .to_vec())
+}
+
+fn demo_payload(url: &str) -> Option<&'static str> {
+    match url {
+        MARINADE_VALIDATORS_URL => Some(include_str!("../assets/demo/marinade-validators.json")),
+        COINGECKO_URL => Some(include_str!("../assets/demo/coingecko.json")),
+        PYTH_URL => Some(include_str!("../assets/demo/pyth.json")),
+        _ if url.ends_with(EPOCH_INFO_METHOD) => Some(include_str!("../assets/demo/epoch-info.json")),
+        _ => None,
+    }
+}
+
+/// `https://host/a/b?c=d` becomes `host_a_b_c_d.json`
+fn fixture_path(dir: &Path, url: &str) -> PathBuf {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let name: String = without_scheme
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    dir.join(format!("{}.json", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn deadline_cuts_a_stalled_fetch_short() {
+        // Accepts connections and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/stalled", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let upstream = Upstream::live().with_deadline(Duration::from_millis(300));
+        let started = Instant::now();
+        let err = upstream
+            .get_json::<serde_json::Value>(&url, upstream.request_timeout(Duration::from_secs(30)))
+            .await
+            .unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+        assert!(format!("{:#}", err).contains(&url), "{:#}", err);
+    }
+
+    /// Answers one request with `body`, then stops listening
+    async fn serve_once(body: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1/price?ids=solana", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = socket.read(&mut request).await;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        url
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("delegation-oracle-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fixtures_are_named_after_the_url() {
+        let dir = Path::new("fixtures");
+        assert_eq!(
+            fixture_path(dir, "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd"),
+            dir.join("api.coingecko.com_api_v3_simple_price_ids_solana_vs_currencies_usd.json")
+        );
+        assert_eq!(
+            fixture_path(dir, "https://api.mainnet-beta.solana.com#getEpochInfo"),
+            dir.join("api.mainnet-beta.solana.com_getEpochInfo.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn recorded_payloads_replay_byte_for_byte() {
+        let dir = temp_dir("record-replay");
+        let url = serve_once(r#"{"solana":{"usd":123.45}}"#).await;
+
+        let recorder = Upstream::new(Mode::Record(dir.clone())).unwrap();
+        let live: serde_json::Value = recorder.get_json(&url, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(fixture_path(&dir, &url)).unwrap(),
+            r#"{"solana":{"usd":123.45}}"#
+        );
+
+        // The server is gone, so this can only come from the fixture
+        let replayer = Upstream::new(Mode::Replay(dir.clone())).unwrap();
+        let replayed: serde_json::Value = replayer.get_json(&url, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(replayed, live);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn load_json_records_and_replays_the_fetched_value() {
+        let dir = temp_dir("load-json");
+        let url = "https://rpc.example#getThing";
+
+        let recorder = Upstream::new(Mode::Record(dir.clone())).unwrap();
+        let fetched: Vec<u64> = recorder.load_json(url, async { Ok(vec![1, 2, 3]) }).await.unwrap();
+        assert_eq!(fetched, [1, 2, 3]);
+
+        let replayer = Upstream::new(Mode::Replay(dir.clone())).unwrap();
+        let replayed: Vec<u64> = replayer
+            .load_json(url, async { anyhow::bail!("replay must not fetch") })
+            .await
+            .unwrap();
+        assert_eq!(replayed, [1, 2, 3]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn replay_without_a_fixture_names_the_missing_file() {
+        let dir = temp_dir("missing");
+        let replayer = Upstream::new(Mode::Replay(dir.clone())).unwrap();
+        let err = replayer
+            .get_json::<serde_json::Value>("https://example.com/a", Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("example.com_a.json"), "{}", err);
+    }
+}
@@ -0,0 +1,36 @@
+//! Multi-program delegation scanner for Solana validators
+//!
+//! Most embedders only need [`DelegationOracle`]:
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use delegation_oracle::{price::PriceSource, DelegationOracle};
+//!
+//! let oracle = DelegationOracle::new().with_price_source(PriceSource::Pyth);
+//! for gap in oracle.gaps("Lua298Woc4rgcswL64yfWAL4EW44FgBZeLsKforf6tJ").await? {
+//!     println!("{} (+{:.0} SOL)", gap.action, gap.potential_gain_sol);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod types;
+pub mod scanners;
+#[cfg(feature = "server")]
+pub mod api;
+#[cfg(feature = "server")]
+mod cache;
+pub mod calendar;
+#[cfg(feature = "dashboard")]
+mod dashboard;
+pub mod doctor;
+pub mod fail_on;
+pub mod fixtures;
+mod oracle;
+pub mod output;
+pub mod price;
+#[cfg(feature = "server")]
+pub mod ratelimit;
+pub mod schema;
+
+pub use oracle::DelegationOracle;
@@ -1,11 +1,25 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
 
-mod types;
-mod scanners;
-mod api;
+use delegation_oracle::{
+    calendar, doctor, fail_on, fixtures, output, scanners,
+    fail_on::FailOn,
+    fixtures::{Mode, Upstream},
+    output::OutputFormat,
+    price::{PriceFeed, PriceSource},
+    schema::SchemaType,
+    types::ScanResult,
+};
+#[cfg(feature = "server")]
+use delegation_oracle::{api, ratelimit};
 
-use types::*;
+/// How long a command may run past `--timeout` before it is abandoned outright
+const TIMEOUT_GRACE: Duration = Duration::from_secs(2);
 
 #[derive(Debug, Parser)]
 #[command(name = "delegation-oracle")]
@@ -14,63 +28,237 @@ use types::*;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    
+    /// SOL/USD price source
+    #[arg(long, global = true, env = "DELEGATION_ORACLE_PRICE_SOURCE", default_value = "coingecko")]
+    price_source: PriceFeed,
+    
+    /// Fixed SOL/USD price, skips the price feed entirely
+    #[arg(long, global = true, env = "DELEGATION_ORACLE_SOL_PRICE", value_parser = positive_price)]
+    sol_price: Option<f64>,
+    
+    /// Plain status text instead of emoji markers in tables (also set by a
+    /// non-empty NO_COLOR)
+    #[arg(long, global = true)]
+    no_color: bool,
+    
+    /// Draw tables with ASCII characters only (implies plain status text)
+    #[arg(long, global = true)]
+    ascii_tables: bool,
+    
+    /// Truncate table output to this many columns
+    #[arg(long, global = true)]
+    max_width: Option<usize>,
+    
+    /// Cut upstream fetches short after this many seconds in total; programs
+    /// still missing are reported as unknown with the fetch that timed out
+    #[arg(long, global = true, env = "DELEGATION_ORACLE_TIMEOUT", value_name = "SECS",
+          value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+    
+    /// Save every upstream payload into this directory
+    #[arg(long, global = true, value_name = "DIR", conflicts_with_all = ["replay", "demo"])]
+    record: Option<PathBuf>,
+    
+    /// Serve upstream fetches from payloads saved with --record (no network)
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "demo")]
+    replay: Option<PathBuf>,
+    
+    /// Use built-in sample data instead of the network; the validator argument
+    /// defaults to a demo validator
+    #[arg(long, global = true)]
+    demo: bool,
 }
 
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// Scan a validator across all delegation programs
+    ///
+    /// Programs that could not be fetched are reported as unknown with a
+    /// fetch_error, and the command exits with code 4. So does a scan whose
+    /// USD figures use the fallback SOL price.
     Scan {
         /// Validator vote account pubkey
-        validator: String,
+        #[arg(env = "DELEGATION_ORACLE_VALIDATOR", required_unless_present = "demo")]
+        validator: Option<String>,
         
         /// Specific program to scan (optional)
-        #[arg(long)]
+        #[arg(long, env = "DELEGATION_ORACLE_PROGRAM")]
         program: Option<String>,
         
         /// Output format
         #[arg(long, default_value = "table")]
         output: OutputFormat,
+        
+        /// Print JSON on a single line instead of pretty-printed
+        #[arg(long)]
+        compact: bool,
+        
+        /// Stable tab-separated output for scripts (overrides --output)
+        #[arg(long)]
+        porcelain: bool,
+        
+        /// Exit with code 3 when a condition matches, e.g. `ineligible:jito,marinade`,
+        /// `not-active:sfdp` or `unknown` (repeatable; no program list means any)
+        #[arg(long, value_name = "CONDITION")]
+        fail_on: Vec<FailOn>,
+    },
+    
+    /// Write a shareable HTML report for a validator
+    ///
+    /// A report with programs that could not be fetched, or with the fallback
+    /// SOL price, is still written, and the command exits with code 4.
+    Report {
+        /// Validator vote account pubkey
+        #[arg(env = "DELEGATION_ORACLE_VALIDATOR", required_unless_present = "demo")]
+        validator: Option<String>,
+        
+        /// Specific program to include (optional)
+        #[arg(long, env = "DELEGATION_ORACLE_PROGRAM")]
+        program: Option<String>,
+        
+        /// File to write the report to
+        #[arg(long, default_value = "report.html")]
+        out: PathBuf,
     },
     
     /// Start the REST API server
+    #[cfg(feature = "server")]
     Serve {
         /// Port to listen on
-        #[arg(long, default_value_t = 3003)]
+        #[arg(long, env = "DELEGATION_ORACLE_PORT", default_value_t = 3003)]
         port: u16,
         
         /// Host to bind to
-        #[arg(long, default_value = "0.0.0.0")]
+        #[arg(long, env = "DELEGATION_ORACLE_HOST", default_value = "0.0.0.0")]
         host: String,
+        
+        /// Requests per minute allowed per client IP (0 disables limiting)
+        #[arg(long, env = "DELEGATION_ORACLE_RATE_LIMIT", default_value_t = 60)]
+        rate_limit: u32,
+        
+        /// Requests a client IP may burst above the steady rate
+        #[arg(long, env = "DELEGATION_ORACLE_RATE_LIMIT_BURST", default_value_t = 10)]
+        rate_limit_burst: u32,
+        
+        /// Seconds to cache /api/scan responses (0 disables caching)
+        #[arg(long, env = "DELEGATION_ORACLE_CACHE_TTL", default_value_t = 60)]
+        cache_ttl: u64,
     },
     
     /// List supported programs
     Programs,
-}
-
-#[derive(Debug, Clone, clap::ValueEnum)]
-enum OutputFormat {
-    Table,
-    Json,
+    
+    /// Show when each program next revisits its delegations
+    Calendar {
+        /// Solana RPC endpoint used for the current epoch
+        #[arg(long, env = "DELEGATION_ORACLE_RPC_URL", default_value = calendar::DEFAULT_RPC_URL)]
+        rpc_url: String,
+    },
+    
+    /// Check price feeds, program endpoints, the Solana RPC and clock skew
+    Doctor {
+        /// Solana RPC endpoint to check
+        #[arg(long, env = "DELEGATION_ORACLE_RPC_URL", default_value = calendar::DEFAULT_RPC_URL)]
+        rpc_url: String,
+    },
+    
+    /// Print the JSON Schema for an output type
+    Schema {
+        /// Output type to describe
+        #[arg(value_enum, default_value = "scan-result")]
+        r#type: SchemaType,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
-    
     let cli = Cli::parse();
     
+    // RUST_LOG overrides; by default the server logs each request and
+    // one-shot commands stay quiet apart from errors
+    let default_filter = match cli.command {
+        #[cfg(feature = "server")]
+        Commands::Serve { .. } => "info",
+        _ => "error",
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter)),
+        )
+        .init();
+    let price_source = PriceSource::from_args(cli.price_source, cli.sol_price);
+    let demo = cli.demo;
+    let table_options = output::TableOptions {
+        no_color: cli.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+        ascii: cli.ascii_tables,
+        max_width: cli.max_width,
+    };
+    let timeout = cli.timeout.map(Duration::from_secs);
+    let upstream = if let Some(dir) = cli.record {
+        Upstream::new(Mode::Record(dir))?
+    } else if let Some(dir) = cli.replay {
+        Upstream::new(Mode::Replay(dir))?
+    } else if cli.demo {
+        Upstream::new(Mode::Demo)?
+    } else {
+        Upstream::live()
+    };
+    // The server runs until stopped, so only one-shot commands get a deadline
+    let one_shot = match timeout {
+        Some(timeout) => upstream.clone().with_deadline(timeout),
+        None => upstream.clone(),
+    };
+    
     match cli.command {
-        Commands::Scan { validator, program, output } => {
-            let result = scanners::scan_validator(&validator, program.as_deref()).await?;
+        Commands::Scan { validator, program, output, compact, porcelain, fail_on } => {
+            let validator = validator_or_demo(validator, demo);
+            let result = within(timeout, scanners::scan_validator(&validator, program.as_deref(), &price_source, &one_shot)).await?;
             
             match output {
-                OutputFormat::Table => print_table(&result),
-                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+                _ if porcelain => print!("{}", output::render_porcelain(&result)),
+                OutputFormat::Table => output::print_table(&result, &table_options),
+                OutputFormat::Json => println!("{}", output::render_json(&result, compact)),
+                OutputFormat::Markdown => print!("{}", output::render_markdown(&result)),
+                OutputFormat::Csv => output::write_csv(&result, std::io::stdout())?,
+                OutputFormat::Jsonl => output::write_jsonl(&result, std::io::stdout())?,
+                OutputFormat::Yaml => print!("{}", output::render_yaml(&result)),
+                OutputFormat::Prometheus => print!("{}", output::render_prometheus(&result)),
+            }
+            
+            warn_partial(&result, &price_source);
+            
+            let failures: Vec<String> = fail_on.iter().flat_map(|f| f.check(&result)).collect();
+            if !failures.is_empty() {
+                for failure in &failures {
+                    eprintln!("fail-on: {}", failure);
+                }
+                std::process::exit(fail_on::EXIT_CODE);
+            }
+            
+            if result.is_partial() {
+                std::process::exit(scanners::PARTIAL_EXIT_CODE);
             }
         }
         
-        Commands::Serve { port, host } => {
-            api::serve(&host, port).await?;
+        Commands::Report { validator, program, out } => {
+            let validator = validator_or_demo(validator, demo);
+            let result = within(timeout, scanners::scan_validator(&validator, program.as_deref(), &price_source, &one_shot)).await?;
+            std::fs::write(&out, output::render_html(&result))?;
+            println!("Report written to {}", out.display());
+            
+            warn_partial(&result, &price_source);
+            if result.is_partial() {
+                std::process::exit(scanners::PARTIAL_EXIT_CODE);
+            }
+        }
+        
+        #[cfg(feature = "server")]
+        Commands::Serve { port, host, rate_limit, rate_limit_burst, cache_ttl } => {
+            let limiter = (rate_limit > 0)
+                .then(|| ratelimit::RateLimiter::new(rate_limit, rate_limit_burst));
+            let cache_ttl = Duration::from_secs(cache_ttl);
+            api::serve(&host, port, price_source, upstream, limiter, cache_ttl).await?;
         }
         
         Commands::Programs => {
@@ -81,53 +269,72 @@ async fn main() -> Result<()> {
             println!("  - sanctum   : Sanctum Gauge (vSOL)");
             println!("  - sfdp      : Solana Foundation Delegation Program");
         }
+        
+        Commands::Calendar { rpc_url } => {
+            let info = within(timeout, calendar::epoch_info(&rpc_url, &one_shot)).await?;
+            calendar::print_calendar(&info);
+        }
+        
+        Commands::Doctor { rpc_url } => {
+            if matches!(upstream.mode(), Some(Mode::Replay(_) | Mode::Demo)) {
+                anyhow::bail!("doctor checks the live upstreams; drop --demo/--replay");
+            }
+            let checks = within(timeout, async { Ok(doctor::run(&price_source, &rpc_url, &one_shot).await) }).await?;
+            doctor::print_report(&checks);
+            if checks.iter().any(|c| c.level == doctor::Level::Fail) {
+                anyhow::bail!("doctor found failing checks");
+            }
+        }
+        
+        Commands::Schema { r#type } => {
+            println!("{}", serde_json::to_string_pretty(&r#type.schema())?);
+        }
     }
     
     Ok(())
 }
 
-fn print_table(result: &ScanResult) {
-    println!("\nValidator: {}", result.validator);
-    println!("Scanned: {}\n", result.scanned_at);
-    
-    println!("┌────────────────┬─────────────┬────────────┬────────────┬──────────┐");
-    println!("│ PROGRAM        │ STATUS      │ CURRENT    │ POTENTIAL  │ GAP      │");
-    println!("├────────────────┼─────────────┼────────────┼────────────┼──────────┤");
-    
+/// Say on stderr which parts of a partial result are missing or estimated
+fn warn_partial(result: &ScanResult, price_source: &PriceSource) {
     for p in &result.programs {
-        let status_str = match p.status {
-            RegistrationStatus::Active => "✅ Active",
-            RegistrationStatus::Eligible => "⚠️ Eligible",
-            RegistrationStatus::NotRegistered => "❌ Not Reg",
-            RegistrationStatus::Ineligible => "🚫 Ineligible",
-            RegistrationStatus::Unknown => "❓ Unknown",
-        };
-        
-        println!(
-            "│ {:<14} │ {:<11} │ {:>10.0} │ {:>10.0} │ {:>+8.0} │",
-            p.display_name,
-            status_str,
-            p.current_stake_sol,
-            p.potential_stake_sol,
-            p.gap_sol
+        if let Some(error) = &p.fetch_error {
+            eprintln!("warning: {} could not be fetched: {}", p.name, error);
+        }
+    }
+    if result.summary.price_is_fallback {
+        eprintln!(
+            "warning: SOL price could not be fetched from {}, USD figures use a fallback of ${:.2}",
+            price_source.name(),
+            result.summary.sol_price_usd
         );
     }
-    
-    println!("└────────────────┴─────────────┴────────────┴────────────┴──────────┘\n");
-    
-    println!("SUMMARY:");
-    println!("  Current Stake:    {:>12.0} SOL", result.summary.total_current_sol);
-    println!("  Potential Stake:  {:>12.0} SOL", result.summary.total_potential_sol);
-    println!("  Missed Revenue:   {:>12.0} SOL/year", result.summary.missed_revenue_sol);
-    println!("  Missed Revenue:   ${:>11.0} USD/year\n", result.summary.missed_revenue_usd);
-    
-    if !result.summary.action_items.is_empty() {
-        println!("ACTION ITEMS:");
-        for (i, action) in result.summary.action_items.iter().enumerate() {
-            println!("  {}. {} (+{:.0} SOL)", i + 1, action.action, action.potential_gain_sol);
-            if let Some(url) = &action.url {
-                println!("     → {}", url);
-            }
-        }
+}
+
+fn positive_price(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(price) if price.is_finite() && price > 0.0 => Ok(price),
+        Ok(_) => Err("must be a positive number".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The validator argument, or the demo validator under `--demo`
+fn validator_or_demo(validator: Option<String>, demo: bool) -> String {
+    match validator {
+        Some(validator) => validator,
+        None if demo => fixtures::DEMO_VALIDATOR.to_string(),
+        None => unreachable!("clap requires a validator unless --demo is set"),
     }
 }
+
+/// Run a one-shot command under `--timeout`, if one was given. Fetches stop at
+/// the deadline on their own and name themselves in the result; this is only a
+/// backstop for a command that somehow outlives it.
+async fn within<T>(timeout: Option<Duration>, command: impl Future<Output = Result<T>>) -> Result<T> {
+    let Some(timeout) = timeout else {
+        return command.await;
+    };
+    tokio::time::timeout(timeout + TIMEOUT_GRACE, command)
+        .await
+        .map_err(|_| anyhow::anyhow!("timed out after {}s", timeout.as_secs()))?
+}
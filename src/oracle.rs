@@ -0,0 +1,64 @@
+//! High-level entry point for embedding the oracle in other services
+
+use anyhow::Result;
+
+use crate::fixtures::Upstream;
+use crate::price::PriceSource;
+use crate::scanners;
+use crate::types::*;
+
+/// Scans validators across delegation programs with a fixed configuration
+#[derive(Debug, Clone)]
+pub struct DelegationOracle {
+    price_source: PriceSource,
+    upstream: Upstream,
+}
+
+impl Default for DelegationOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DelegationOracle {
+    /// Oracle pricing USD figures from CoinGecko
+    pub fn new() -> Self {
+        Self {
+            price_source: PriceSource::CoinGecko,
+            upstream: Upstream::live(),
+        }
+    }
+
+    pub fn with_price_source(mut self, price_source: PriceSource) -> Self {
+        self.price_source = price_source;
+        self
+    }
+
+    /// Record, replay or demo fetches for this oracle only
+    pub fn with_upstream(mut self, upstream: Upstream) -> Self {
+        self.upstream = upstream;
+        self
+    }
+
+    /// Status of a validator in every program
+    pub async fn status(&self, validator: &str) -> Result<ScanResult> {
+        scanners::scan_validator(validator, None, &self.price_source, &self.upstream).await
+    }
+
+    /// Status of a validator in one program, by slug (see `PROGRAM_NAMES`)
+    pub async fn program_status(&self, validator: &str, program: &str) -> Result<ProgramStatus> {
+        let mut result = scanners::scan_validator(validator, Some(program), &self.price_source, &self.upstream).await?;
+        Ok(result.programs.remove(0))
+    }
+
+    /// Status of several validators, fetching program data only once
+    pub async fn status_many(&self, validators: &[String]) -> Result<Vec<ScanResult>> {
+        scanners::scan_validators(validators, None, &self.price_source, &self.upstream).await
+    }
+
+    /// Action items for the programs where a validator is leaving stake behind,
+    /// easiest first, in the same order as the scan summary and its milestones
+    pub async fn gaps(&self, validator: &str) -> Result<Vec<ActionItem>> {
+        Ok(self.status(validator).await?.summary.action_items)
+    }
+}
@@ -0,0 +1,73 @@
+//! CSV rendering, one row per program
+
+use std::io::Write;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::types::*;
+
+#[derive(Serialize)]
+struct ProgramRow<'a> {
+    validator: &'a str,
+    scanned_at: String,
+    program: &'a str,
+    display_name: &'a str,
+    status: &'a RegistrationStatus,
+    current_stake_sol: f64,
+    potential_stake_sol: f64,
+    gap_sol: f64,
+    registration_url: Option<&'a str>,
+    fetch_error: Option<&'a str>,
+}
+
+pub fn write_csv(result: &ScanResult, out: impl Write) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    let scanned_at = result.scanned_at.to_rfc3339();
+
+    for p in &result.programs {
+        writer.serialize(ProgramRow {
+            validator: &result.validator,
+            scanned_at: scanned_at.clone(),
+            program: &p.name,
+            display_name: &p.display_name,
+            status: &p.status,
+            current_stake_sol: p.current_stake_sol,
+            potential_stake_sol: p.potential_stake_sol,
+            gap_sol: p.gap_sol,
+            registration_url: p.registration_url.as_deref(),
+            fetch_error: p.fetch_error.as_deref(),
+        })?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::sample_result;
+
+    #[test]
+    fn columns_are_stable() {
+        let mut out = Vec::new();
+        write_csv(&sample_result(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[0],
+            "validator,scanned_at,program,display_name,status,current_stake_sol,potential_stake_sol,gap_sol,registration_url,fetch_error"
+        );
+        assert_eq!(
+            lines[2],
+            "Va1idator1111111111111111111111111111111111,2026-01-02T03:04:05+00:00,jito,Jito | StakeNet,unknown,0.0,800.0,800.0,https://jito.network/stakenet,timed out\tafter 2s"
+        );
+        assert_eq!(
+            lines[3],
+            "Va1idator1111111111111111111111111111111111,2026-01-02T03:04:05+00:00,sanctum,Sanctum Gauge,not_registered,0.0,1000.0,1000.0,,"
+        );
+    }
+}
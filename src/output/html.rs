@@ -0,0 +1,169 @@
+//! Self-contained HTML report for sharing scan results
+
+use std::fmt::{self, Write};
+
+use crate::types::*;
+
+const STYLE: &str = r#"
+body { font-family: system-ui, -apple-system, sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; color: #1f2430; }
+h1 { font-size: 1.5rem; margin-bottom: 0; }
+h2 { font-size: 1.15rem; margin-top: 2rem; border-bottom: 1px solid #dde1ea; padding-bottom: 0.3rem; }
+.meta { color: #6b7280; font-size: 0.85rem; }
+.cards { display: grid; grid-template-columns: repeat(auto-fit, minmax(10rem, 1fr)); gap: 0.75rem; margin-top: 1.5rem; }
+.card { border: 1px solid #dde1ea; border-radius: 6px; padding: 0.75rem; }
+.card span { display: block; font-size: 0.8rem; color: #6b7280; }
+.card strong { font-size: 1.25rem; }
+table { width: 100%; border-collapse: collapse; }
+th, td { padding: 0.45rem; border-bottom: 1px solid #eceff4; text-align: left; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+.chart-row { display: grid; grid-template-columns: 10rem 1fr 6rem; align-items: center; gap: 0.5rem; margin: 0.4rem 0; }
+.bar { position: relative; height: 1.1rem; background: #eceff4; border-radius: 3px; }
+.bar .potential { position: absolute; inset: 0 auto 0 0; background: #c5d0f5; border-radius: 3px; }
+.bar .current { position: absolute; inset: 0 auto 0 0; background: #3b5bdb; border-radius: 3px; }
+.legend span { display: inline-block; width: 0.8rem; height: 0.8rem; border-radius: 2px; margin: 0 0.3rem 0 1rem; vertical-align: middle; }
+.demo { background: #fff3bf; border: 1px solid #f59f00; border-radius: 6px; padding: 0.6rem 0.9rem; }
+.active { color: #2b8a3e; } .eligible { color: #e67700; } .not_registered, .ineligible { color: #c92a2a; } .unknown { color: #6b7280; }
+"#;
+
+pub fn render_html(result: &ScanResult) -> String {
+    let mut out = String::new();
+    write_html(&mut out, result).expect("writing to a String cannot fail");
+    out
+}
+
+fn write_html(out: &mut String, result: &ScanResult) -> fmt::Result {
+    let summary = &result.summary;
+
+    writeln!(out, "<!doctype html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>Delegation report: {}</title>", escape(&result.validator))?;
+    writeln!(out, "<style>{}</style>\n</head>\n<body>", STYLE)?;
+
+    writeln!(out, "<h1>Delegation report</h1>")?;
+    if result.demo {
+        writeln!(out, "<p class=\"demo\"><strong>Demo data.</strong> Built from built-in sample payloads, not a live scan.</p>")?;
+    }
+    writeln!(
+        out,
+        "<p class=\"meta\">Validator <code>{}</code> &middot; scanned {} &middot; SOL price ${:.2}{}</p>",
+        escape(&result.validator),
+        result.scanned_at.format("%Y-%m-%d %H:%M UTC"),
+        summary.sol_price_usd,
+        if summary.price_is_fallback { " (fallback, price feed unreachable)" } else { "" }
+    )?;
+
+    writeln!(out, "<div class=\"cards\">")?;
+    for (label, value) in [
+        ("Current stake", format!("{:.0} SOL", summary.total_current_sol)),
+        ("Potential stake", format!("{:.0} SOL", summary.total_potential_sol)),
+        ("Missed revenue", format!("{:.0} SOL/yr", summary.missed_revenue_sol)),
+        ("Missed revenue (USD)", format!("${:.0}/yr", summary.missed_revenue_usd)),
+    ] {
+        writeln!(out, "<div class=\"card\"><span>{}</span><strong>{}</strong></div>", label, value)?;
+    }
+    writeln!(out, "</div>")?;
+
+    writeln!(out, "<h2>Current vs potential stake</h2>")?;
+    writeln!(
+        out,
+        "<p class=\"legend meta\"><span style=\"background:#3b5bdb\"></span>current<span style=\"background:#c5d0f5\"></span>potential</p>"
+    )?;
+    let scale = result
+        .programs
+        .iter()
+        .map(|p| p.potential_stake_sol.max(p.current_stake_sol))
+        .fold(0.0, f64::max);
+    for p in &result.programs {
+        writeln!(
+            out,
+            "<div class=\"chart-row\"><span>{}</span><div class=\"bar\"><div class=\"potential\" style=\"width:{:.1}%\"></div><div class=\"current\" style=\"width:{:.1}%\"></div></div><span class=\"meta\">{:.0} SOL</span></div>",
+            escape(&p.display_name),
+            percent_of(p.potential_stake_sol, scale),
+            percent_of(p.current_stake_sol, scale),
+            p.potential_stake_sol
+        )?;
+    }
+
+    writeln!(out, "<h2>Programs</h2>")?;
+    writeln!(
+        out,
+        "<table>\n<tr><th>Program</th><th>Status</th><th>Current (SOL)</th><th>Potential (SOL)</th><th>Gap (SOL)</th></tr>"
+    )?;
+    for p in &result.programs {
+        let (class, label) = match p.status {
+            RegistrationStatus::Active => ("active", "Active"),
+            RegistrationStatus::Eligible => ("eligible", "Eligible"),
+            RegistrationStatus::NotRegistered => ("not_registered", "Not registered"),
+            RegistrationStatus::Ineligible => ("ineligible", "Ineligible"),
+            RegistrationStatus::Unknown => ("unknown", "Unknown"),
+        };
+        let note = match &p.fetch_error {
+            Some(error) => format!("<br><span class=\"meta\">could not be fetched: {}</span>", escape(error)),
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"{}\">{}{}</td><td class=\"num\">{:.0}</td><td class=\"num\">{:.0}</td><td class=\"num\">{:+.0}</td></tr>",
+            escape(&p.display_name),
+            class,
+            label,
+            note,
+            p.current_stake_sol,
+            p.potential_stake_sol,
+            p.gap_sol
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    if !summary.action_items.is_empty() {
+        writeln!(out, "<h2>Action items</h2>\n<ol>")?;
+        for action in &summary.action_items {
+            write!(
+                out,
+                "<li>{} (+{:.0} SOL, {:?})",
+                escape(&action.action),
+                action.potential_gain_sol,
+                action.difficulty
+            )?;
+            if let Some(url) = &action.url {
+                write!(out, " &mdash; <a href=\"{}\">register</a>", escape(url))?;
+            }
+            writeln!(out, "</li>")?;
+        }
+        writeln!(out, "</ol>")?;
+    }
+
+    if summary.milestones.len() > 1 {
+        writeln!(
+            out,
+            "<h2>Milestones</h2>\n<table>\n<tr><th>Difficulty</th><th>Programs</th><th>Gain (SOL)</th><th>Cumulative (SOL)</th></tr>"
+        )?;
+        for m in &summary.milestones {
+            writeln!(
+                out,
+                "<tr><td>{:?}</td><td>{}</td><td class=\"num\">+{:.0}</td><td class=\"num\">{:.0}</td></tr>",
+                m.difficulty,
+                escape(&m.programs.join(", ")),
+                m.gain_sol,
+                m.cumulative_gain_sol
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    writeln!(out, "</body>\n</html>")
+}
+
+fn percent_of(value: f64, scale: f64) -> f64 {
+    if scale > 0.0 {
+        (value / scale * 100.0).clamp(0.0, 100.0)
+    } else {
+        0.0
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
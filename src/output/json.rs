@@ -0,0 +1,40 @@
+//! JSON and JSON Lines rendering for APIs, log shippers and jq pipelines
+
+use std::io::Write;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::types::*;
+
+#[derive(Serialize)]
+struct ProgramRecord<'a> {
+    validator: &'a str,
+    scanned_at: DateTime<Utc>,
+    #[serde(flatten)]
+    program: &'a ProgramStatus,
+}
+
+/// The whole result as one JSON document, pretty-printed unless `compact`
+pub fn render_json(result: &ScanResult, compact: bool) -> String {
+    let json = if compact {
+        serde_json::to_string(result)
+    } else {
+        serde_json::to_string_pretty(result)
+    };
+    json.expect("a scan result always serializes")
+}
+
+pub fn write_jsonl(result: &ScanResult, mut out: impl Write) -> Result<()> {
+    for program in &result.programs {
+        let record = ProgramRecord {
+            validator: &result.validator,
+            scanned_at: result.scanned_at,
+            program,
+        };
+        serde_json::to_writer(&mut out, &record)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
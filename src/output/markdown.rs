@@ -0,0 +1,160 @@
+//! GitHub-flavored markdown rendering, for pasting into issues and runbooks
+
+use std::fmt::{self, Write};
+
+use crate::types::*;
+
+use super::{score_summary, status_label};
+
+pub fn render_markdown(result: &ScanResult) -> String {
+    let mut out = String::new();
+    write_markdown(&mut out, result).expect("writing to a String cannot fail");
+    out
+}
+
+fn write_markdown(out: &mut String, result: &ScanResult) -> fmt::Result {
+    let summary = &result.summary;
+
+    writeln!(out, "## Delegation scan: `{}`\n", result.validator)?;
+    writeln!(out, "Scanned {}\n", result.scanned_at.format("%Y-%m-%d %H:%M UTC"))?;
+    if result.demo {
+        writeln!(out, "> **Demo data:** built-in sample payloads, not a live scan.\n")?;
+    }
+
+    writeln!(out, "| Program | Status | Current (SOL) | Potential (SOL) | Gap (SOL) |")?;
+    writeln!(out, "|---|---|--:|--:|--:|")?;
+    for p in &result.programs {
+        writeln!(
+            out,
+            "| {} | {} | {:.0} | {:.0} | {:+.0} |",
+            escape(&p.display_name),
+            status_label(&p.status),
+            p.current_stake_sol,
+            p.potential_stake_sol,
+            p.gap_sol
+        )?;
+    }
+
+    for p in &result.programs {
+        if let Some(error) = &p.fetch_error {
+            writeln!(out, "\n> **{}** could not be fetched: {}", escape(&p.display_name), escape(error))?;
+        }
+    }
+    for p in &result.programs {
+        if let Some(score) = score_summary(p, false) {
+            writeln!(out, "\n{}: {}", escape(&p.display_name), score)?;
+        }
+    }
+
+    writeln!(out, "\n### Summary\n")?;
+    writeln!(out, "- **Current stake:** {:.0} SOL", summary.total_current_sol)?;
+    writeln!(out, "- **Potential stake:** {:.0} SOL", summary.total_potential_sol)?;
+    writeln!(
+        out,
+        "- **Missed revenue:** {:.0} SOL/year (${:.0} USD/year at ${:.2}/SOL{})",
+        summary.missed_revenue_sol,
+        summary.missed_revenue_usd,
+        summary.sol_price_usd,
+        if summary.price_is_fallback { ", fallback price" } else { "" }
+    )?;
+
+    if !summary.action_items.is_empty() {
+        writeln!(out, "\n### Action items\n")?;
+        for action in &summary.action_items {
+            write!(
+                out,
+                "- [ ] {} (+{:.0} SOL, {:?})",
+                escape(&action.action),
+                action.potential_gain_sol,
+                action.difficulty
+            )?;
+            if let Some(url) = &action.url {
+                write!(out, " — [register]({})", url)?;
+            }
+            out.push('\n');
+        }
+    }
+
+    if summary.milestones.len() > 1 {
+        writeln!(out, "\n### Milestones\n")?;
+        writeln!(out, "| Difficulty | Programs | Gain (SOL) | Cumulative (SOL) |")?;
+        writeln!(out, "|---|---|--:|--:|")?;
+        for m in &summary.milestones {
+            writeln!(
+                out,
+                "| {:?} | {} | +{:.0} | {:.0} |",
+                m.difficulty,
+                escape(&m.programs.join(", ")),
+                m.gain_sol,
+                m.cumulative_gain_sol
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Keep user-facing text from breaking table cells
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::sample_result;
+
+    #[test]
+    fn renders_the_sample_scan() {
+        let expected = "\
+## Delegation scan: `Va1idator1111111111111111111111111111111111`
+
+Scanned 2026-01-02 03:04 UTC
+
+| Program | Status | Current (SOL) | Potential (SOL) | Gap (SOL) |
+|---|---|--:|--:|--:|
+| Marinade | ✅ Active | 1850 | 1850 | +0 |
+| Jito \\| StakeNet | ❓ Unknown | 0 | 800 | +800 |
+| Sanctum Gauge | ❌ Not Reg | 0 | 1000 | +1000 |
+| SolBlaze | 🚫 Ineligible | 0 | 400 | +400 |
+
+> **Jito \\| StakeNet** could not be fetched: timed out\tafter 2s
+
+Marinade: score 0.91 — 100th pct, median 0.875
+
+### Summary
+
+- **Current stake:** 1850 SOL
+- **Potential stake:** 4050 SOL
+- **Missed revenue:** 2200 SOL/year ($330000 USD/year at $150.00/SOL)
+
+### Action items
+
+- [ ] Register with Jito \\| StakeNet (+800 SOL, Easy) — [register](https://jito.network/stakenet)
+";
+        assert_eq!(render_markdown(&sample_result()), expected);
+    }
+
+    #[test]
+    fn renders_milestones_once_there_is_more_than_one() {
+        let mut result = sample_result();
+        result.summary.milestones.push(Milestone {
+            difficulty: Difficulty::Hard,
+            programs: vec!["sanctum".to_string(), "blaze".to_string()],
+            gain_sol: 1400.0,
+            cumulative_gain_sol: 2200.0,
+        });
+        assert!(render_markdown(&result).ends_with(
+            "\n### Milestones\n\n\
+             | Difficulty | Programs | Gain (SOL) | Cumulative (SOL) |\n\
+             |---|---|--:|--:|\n\
+             | Easy | jito | +800 | 800 |\n\
+             | Hard | sanctum, blaze | +1400 | 2200 |\n"
+        ));
+    }
+
+    #[test]
+    fn escapes_pipes_in_cells() {
+        assert_eq!(escape("a|b||c"), "a\\|b\\|\\|c");
+    }
+}
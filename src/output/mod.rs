@@ -0,0 +1,140 @@
+//! Output renderers for scan results
+
+mod csv;
+mod html;
+mod json;
+mod markdown;
+mod porcelain;
+mod prometheus;
+mod table;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use self::csv::write_csv;
+pub use html::render_html;
+pub use json::{render_json, write_jsonl};
+pub use markdown::render_markdown;
+pub use porcelain::render_porcelain;
+pub use prometheus::render_prometheus;
+pub use table::{print_table, TableOptions};
+#[cfg(feature = "yaml")]
+pub use yaml::render_yaml;
+
+use crate::types::{ProgramStatus, RegistrationStatus};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Markdown,
+    Csv,
+    /// One JSON object per program per line
+    Jsonl,
+    Yaml,
+    /// Prometheus text exposition (gauges)
+    Prometheus,
+}
+
+fn status_text(status: &RegistrationStatus) -> &'static str {
+    match status {
+        RegistrationStatus::Active => "Active",
+        RegistrationStatus::Eligible => "Eligible",
+        RegistrationStatus::NotRegistered => "Not Reg",
+        RegistrationStatus::Ineligible => "Ineligible",
+        RegistrationStatus::Unknown => "Unknown",
+    }
+}
+
+/// "score 0.91 — 100th pct, median 0.875" for programs that publish a score;
+/// `ascii` swaps the em dash for a hyphen
+fn score_summary(program: &ProgramStatus, ascii: bool) -> Option<String> {
+    let details = &program.details;
+    let score = details.get("score")?.as_f64()?;
+    let mut text = format!("score {}", score);
+    let percentile = details.get("score_percentile").and_then(|p| p.as_f64());
+    let median = details.get("cohort_median_score").and_then(|m| m.as_f64());
+    if let (Some(percentile), Some(median)) = (percentile, median) {
+        let dash = if ascii { '-' } else { '—' };
+        text.push_str(&format!(" {} {} pct, median {}", dash, ordinal(percentile.round() as u64), median));
+    }
+    Some(text)
+}
+
+fn ordinal(n: u64) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+fn status_label(status: &RegistrationStatus) -> &'static str {
+    match status {
+        RegistrationStatus::Active => "✅ Active",
+        RegistrationStatus::Eligible => "⚠️ Eligible",
+        RegistrationStatus::NotRegistered => "❌ Not Reg",
+        RegistrationStatus::Ineligible => "🚫 Ineligible",
+        RegistrationStatus::Unknown => "❓ Unknown",
+    }
+}
+
+/// One hand-built result shared by the renderer tests
+#[cfg(test)]
+pub(crate) fn sample_result() -> crate::types::ScanResult {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use crate::types::*;
+
+    ScanResult {
+        validator: "Va1idator1111111111111111111111111111111111".to_string(),
+        scanned_at: chrono::Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap(),
+        programs: vec![
+            ProgramStatus::new("marinade", "Marinade")
+                .with_status(RegistrationStatus::Active)
+                .with_stake(1850.0, 1850.0)
+                .with_details(json!({
+                    "score": 0.91,
+                    "eligible": true,
+                    "score_percentile": 100.0,
+                    "cohort_median_score": 0.875,
+                })),
+            ProgramStatus::new("jito", "Jito | StakeNet")
+                .with_stake(0.0, 800.0)
+                .with_registration_url("https://jito.network/stakenet")
+                .with_fetch_error("timed out\tafter 2s"),
+            ProgramStatus::new("sanctum", "Sanctum Gauge")
+                .with_status(RegistrationStatus::NotRegistered)
+                .with_stake(0.0, 1000.0),
+            ProgramStatus::new("blaze", "SolBlaze")
+                .with_status(RegistrationStatus::Ineligible)
+                .with_stake(0.0, 400.0),
+        ],
+        summary: ScanSummary {
+            total_current_sol: 1850.0,
+            total_potential_sol: 4050.0,
+            missed_revenue_sol: 2200.0,
+            missed_revenue_usd: 330000.0,
+            sol_price_usd: 150.0,
+            price_is_fallback: false,
+            action_items: vec![ActionItem {
+                program: "jito".to_string(),
+                action: "Register with Jito | StakeNet".to_string(),
+                potential_gain_sol: 800.0,
+                url: Some("https://jito.network/stakenet".to_string()),
+                difficulty: Difficulty::Easy,
+            }],
+            milestones: vec![Milestone {
+                difficulty: Difficulty::Easy,
+                programs: vec!["jito".to_string()],
+                gain_sol: 800.0,
+                cumulative_gain_sol: 800.0,
+            }],
+        },
+        demo: false,
+    }
+}
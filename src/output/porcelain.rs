@@ -0,0 +1,68 @@
+//! Porcelain output: a parse-stable contract for shell scripts
+//!
+//! One line per program, tab-separated, no header:
+//!
+//! ```text
+//! v1  <program>  <status>  <eligible>  <current_sol>  <potential_sol>  <gap_sol>  <score>  <fetch_error>
+//! ```
+//!
+//! `eligible` is `1`, `0`, or `-` when the program hasn't said (not registered
+//! or unknown). Stake figures are whole SOL. `score` is the program's own score
+//! for the validator, `-` when it doesn't publish one. `fetch_error` is why the
+//! program's data could not be fetched, on one line, or `-`. New fields are only
+//! ever appended; anything else bumps the version.
+
+use crate::types::*;
+
+pub const PORCELAIN_VERSION: &str = "v1";
+
+pub fn render_porcelain(result: &ScanResult) -> String {
+    let mut out = String::new();
+    for p in &result.programs {
+        let (status, eligible) = match p.status {
+            RegistrationStatus::Active => ("active", "1"),
+            RegistrationStatus::Eligible => ("eligible", "1"),
+            RegistrationStatus::NotRegistered => ("not_registered", "-"),
+            RegistrationStatus::Ineligible => ("ineligible", "0"),
+            RegistrationStatus::Unknown => ("unknown", "-"),
+        };
+        let score = match p.details.get("score").and_then(|s| s.as_f64()) {
+            Some(score) => score.to_string(),
+            None => "-".to_string(),
+        };
+        let fetch_error = match &p.fetch_error {
+            Some(error) => error.replace(['\t', '\n', '\r'], " "),
+            None => "-".to_string(),
+        };
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{:.0}\t{:.0}\t{:.0}\t{}\t{}\n",
+            PORCELAIN_VERSION,
+            p.name,
+            status,
+            eligible,
+            p.current_stake_sol,
+            p.potential_stake_sol,
+            p.gap_sol,
+            score,
+            fetch_error
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::sample_result;
+
+    #[test]
+    fn v1_format_is_stable() {
+        assert_eq!(
+            render_porcelain(&sample_result()),
+            "v1\tmarinade\tactive\t1\t1850\t1850\t0\t0.91\t-\n\
+             v1\tjito\tunknown\t-\t0\t800\t800\t-\ttimed out after 2s\n\
+             v1\tsanctum\tnot_registered\t-\t0\t1000\t1000\t-\t-\n\
+             v1\tblaze\tineligible\t0\t0\t400\t400\t-\t-\n"
+        );
+    }
+}
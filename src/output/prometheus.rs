@@ -0,0 +1,148 @@
+//! Prometheus text exposition, for node_exporter's textfile collector
+
+use std::fmt::{self, Write};
+
+use crate::types::*;
+
+pub fn render_prometheus(result: &ScanResult) -> String {
+    let mut out = String::new();
+    write_prometheus(&mut out, result).expect("writing to a String cannot fail");
+    out
+}
+
+fn write_prometheus(out: &mut String, result: &ScanResult) -> fmt::Result {
+    let validator = escape(&result.validator);
+    let summary = &result.summary;
+
+    header(
+        out,
+        "delegation_oracle_eligible",
+        "1 if the program has or would accept the validator, 0 if it rejects it (omitted when not registered or unknown)",
+    )?;
+    for p in &result.programs {
+        // Not registered says nothing about eligibility, so it is left out like unknown
+        let eligible = match p.status {
+            RegistrationStatus::Active | RegistrationStatus::Eligible => 1,
+            RegistrationStatus::Ineligible => 0,
+            RegistrationStatus::NotRegistered | RegistrationStatus::Unknown => continue,
+        };
+        writeln!(out, "delegation_oracle_eligible{{validator=\"{}\",program=\"{}\"}} {}", validator, escape(&p.name), eligible)?;
+    }
+
+    header(out, "delegation_oracle_status", "Registration status per program, 1 for the current status")?;
+    for p in &result.programs {
+        let status = match p.status {
+            RegistrationStatus::Active => "active",
+            RegistrationStatus::Eligible => "eligible",
+            RegistrationStatus::NotRegistered => "not_registered",
+            RegistrationStatus::Ineligible => "ineligible",
+            RegistrationStatus::Unknown => "unknown",
+        };
+        writeln!(
+            out,
+            "delegation_oracle_status{{validator=\"{}\",program=\"{}\",status=\"{}\"}} 1",
+            validator,
+            escape(&p.name),
+            status
+        )?;
+    }
+
+    program_gauge(out, result, "delegation_oracle_current_stake_sol", "Stake currently delegated by the program", |p| p.current_stake_sol)?;
+    program_gauge(out, result, "delegation_oracle_potential_stake_sol", "Estimated stake the program could delegate", |p| p.potential_stake_sol)?;
+    program_gauge(out, result, "delegation_oracle_gap_sol", "Potential minus current stake", |p| p.gap_sol)?;
+
+    for (name, help, value) in [
+        ("delegation_oracle_missed_revenue_sol", "Missed revenue across programs in SOL per year", summary.missed_revenue_sol),
+        ("delegation_oracle_missed_revenue_usd", "Missed revenue across programs in USD per year", summary.missed_revenue_usd),
+        ("delegation_oracle_sol_price_usd", "SOL price used for USD figures", summary.sol_price_usd),
+        (
+            "delegation_oracle_sol_price_is_fallback",
+            "1 if the price feed was unreachable and the fallback price was used",
+            if summary.price_is_fallback { 1.0 } else { 0.0 },
+        ),
+        ("delegation_oracle_scan_timestamp_seconds", "Unix time of the scan", result.scanned_at.timestamp() as f64),
+    ] {
+        header(out, name, help)?;
+        writeln!(out, "{}{{validator=\"{}\"}} {}", name, validator, value)?;
+    }
+
+    Ok(())
+}
+
+fn program_gauge(
+    out: &mut String,
+    result: &ScanResult,
+    name: &str,
+    help: &str,
+    value: impl Fn(&ProgramStatus) -> f64,
+) -> fmt::Result {
+    header(out, name, help)?;
+    for p in &result.programs {
+        writeln!(
+            out,
+            "{}{{validator=\"{}\",program=\"{}\"}} {}",
+            name,
+            escape(&result.validator),
+            escape(&p.name),
+            value(p)
+        )?;
+    }
+    Ok(())
+}
+
+fn header(out: &mut String, name: &str, help: &str) -> fmt::Result {
+    writeln!(out, "# HELP {} {}", name, help)?;
+    writeln!(out, "# TYPE {} gauge", name)
+}
+
+/// Escape a label value per the exposition format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::sample_result;
+
+    const VALIDATOR: &str = "validator=\"Va1idator1111111111111111111111111111111111\"";
+
+    #[test]
+    fn eligible_gauge_omits_not_registered_and_unknown() {
+        let out = render_prometheus(&sample_result());
+        let eligible: Vec<_> = out.lines().filter(|l| l.starts_with("delegation_oracle_eligible{")).collect();
+        assert_eq!(
+            eligible,
+            [
+                format!("delegation_oracle_eligible{{{},program=\"marinade\"}} 1", VALIDATOR),
+                format!("delegation_oracle_eligible{{{},program=\"blaze\"}} 0", VALIDATOR),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_program_gets_status_and_stake_series() {
+        let out = render_prometheus(&sample_result());
+        let count = |prefix: &str| out.lines().filter(|l| l.starts_with(prefix)).count();
+        assert_eq!(count("delegation_oracle_status{"), 4);
+        assert_eq!(count("delegation_oracle_gap_sol{"), 4);
+        assert!(out.contains(&format!(
+            "delegation_oracle_status{{{},program=\"sanctum\",status=\"not_registered\"}} 1",
+            VALIDATOR
+        )));
+        assert!(out.contains(&format!("delegation_oracle_sol_price_is_fallback{{{}}} 0", VALIDATOR)));
+        assert!(out.contains(&format!("delegation_oracle_scan_timestamp_seconds{{{}}} 1767323045", VALIDATOR)));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+
+        let mut result = sample_result();
+        result.validator = "odd\"name".to_string();
+        assert!(render_prometheus(&result).contains("validator=\"odd\\\"name\""));
+    }
+}
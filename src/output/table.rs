@@ -0,0 +1,260 @@
+//! Terminal table rendering
+
+use crate::types::*;
+
+use super::{score_summary, status_label, status_text};
+
+/// Presentation knobs for terminal tables
+#[derive(Debug, Clone, Default)]
+pub struct TableOptions {
+    /// Plain status words instead of emoji markers
+    pub no_color: bool,
+    /// ASCII only: `+-|` borders, plain status words and separators
+    pub ascii: bool,
+    /// Truncate output lines to this many characters
+    pub max_width: Option<usize>,
+}
+
+struct Glyphs {
+    horizontal: char,
+    vertical: char,
+    top: [char; 3],
+    middle: [char; 3],
+    bottom: [char; 3],
+    arrow: &'static str,
+    ellipsis: char,
+}
+
+const UNICODE: Glyphs = Glyphs {
+    horizontal: '─',
+    vertical: '│',
+    top: ['┌', '┬', '┐'],
+    middle: ['├', '┼', '┤'],
+    bottom: ['└', '┴', '┘'],
+    arrow: "→",
+    ellipsis: '…',
+};
+
+const ASCII: Glyphs = Glyphs {
+    horizontal: '-',
+    vertical: '|',
+    top: ['+', '+', '+'],
+    middle: ['+', '+', '+'],
+    bottom: ['+', '+', '+'],
+    arrow: "->",
+    ellipsis: '~',
+};
+
+/// Width of the PROGRAM column unless a name or --max-width demands otherwise
+const PROGRAM_WIDTH: usize = 14;
+/// Narrowest the PROGRAM column gets when squeezed by --max-width
+const MIN_PROGRAM_WIDTH: usize = 7;
+
+pub fn print_table(result: &ScanResult, options: &TableOptions) {
+    let glyphs = if options.ascii { &ASCII } else { &UNICODE };
+    let fit = |line: String| match options.max_width {
+        Some(max) => truncate(&line, max, glyphs.ellipsis),
+        None => line,
+    };
+
+    let widths = column_widths(&result.programs, options.max_width);
+    if let Some(max) = options.max_width {
+        if table_width(&widths) > max {
+            eprintln!(
+                "warning: --max-width {} is narrower than the table ({} columns); rows are cut",
+                max,
+                table_width(&widths)
+            );
+        }
+    }
+
+    // Like URLs, the pubkey is only useful whole
+    println!("\nValidator: {}", result.validator);
+    if result.demo {
+        println!("{}", fit("DEMO DATA: built-in sample payloads, not a live scan".to_string()));
+    }
+    println!("{}\n", fit(format!("Scanned: {}", result.scanned_at)));
+
+    println!("{}", fit(rule(glyphs, &glyphs.top, &widths)));
+    let header = ["PROGRAM", "STATUS", "CURRENT", "POTENTIAL", "GAP"].map(String::from);
+    println!("{}", fit(row(glyphs, &widths, header, false)));
+    println!("{}", fit(rule(glyphs, &glyphs.middle, &widths)));
+
+    for p in &result.programs {
+        let status = if options.no_color || options.ascii { status_text(&p.status) } else { status_label(&p.status) };
+        let cells = [
+            truncate(&p.display_name, widths[0], glyphs.ellipsis),
+            status.to_string(),
+            format!("{:.0}", p.current_stake_sol),
+            format!("{:.0}", p.potential_stake_sol),
+            format!("{:+.0}", p.gap_sol),
+        ];
+        println!("{}", fit(row(glyphs, &widths, cells, true)));
+    }
+
+    println!("{}\n", fit(rule(glyphs, &glyphs.bottom, &widths)));
+
+    let errors: Vec<_> = result
+        .programs
+        .iter()
+        .filter_map(|p| p.fetch_error.as_ref().map(|e| (&p.display_name, e)))
+        .collect();
+    if !errors.is_empty() {
+        println!("FETCH ERRORS:");
+        for (name, error) in errors {
+            println!("{}", fit(format!("  {}: {}", name, error)));
+        }
+        println!();
+    }
+
+    let scores: Vec<_> = result
+        .programs
+        .iter()
+        .filter_map(|p| score_summary(p, options.ascii).map(|s| (&p.display_name, s)))
+        .collect();
+    if !scores.is_empty() {
+        println!("SCORES:");
+        for (name, score) in scores {
+            println!("{}", fit(format!("  {}: {}", name, score)));
+        }
+        println!();
+    }
+
+    println!("SUMMARY:");
+    println!("  Current Stake:    {:>12.0} SOL", result.summary.total_current_sol);
+    println!("  Potential Stake:  {:>12.0} SOL", result.summary.total_potential_sol);
+    println!("  Missed Revenue:   {:>12.0} SOL/year", result.summary.missed_revenue_sol);
+    println!("  Missed Revenue:   ${:>11.0} USD/year", result.summary.missed_revenue_usd);
+    let fallback = if result.summary.price_is_fallback { " (fallback, feed unreachable)" } else { "" };
+    println!("  SOL Price:        ${:>11.2} USD{}\n", result.summary.sol_price_usd, fallback);
+
+    if !result.summary.action_items.is_empty() {
+        println!("ACTION ITEMS:");
+        for (i, action) in result.summary.action_items.iter().enumerate() {
+            println!("{}", fit(format!("  {}. {} (+{:.0} SOL)", i + 1, action.action, action.potential_gain_sol)));
+            // URLs are never cut, a truncated link is useless
+            if let Some(url) = &action.url {
+                println!("     {} {}", glyphs.arrow, url);
+            }
+        }
+    }
+    
+    if result.summary.milestones.len() > 1 {
+        println!("\nMILESTONES:");
+        for m in &result.summary.milestones {
+            println!(
+                "{}",
+                fit(format!(
+                    "  {:?}: {} (+{:.0} SOL, {:.0} SOL total)",
+                    m.difficulty,
+                    m.programs.join(", "),
+                    m.gain_sol,
+                    m.cumulative_gain_sol
+                ))
+            );
+        }
+    }
+}
+
+/// Column widths, squeezing the PROGRAM column towards `max_width`
+fn column_widths(programs: &[ProgramStatus], max_width: Option<usize>) -> [usize; 5] {
+    let longest_name = programs.iter().map(|p| p.display_name.chars().count()).max().unwrap_or(0);
+    let mut widths = [PROGRAM_WIDTH.max(longest_name), 11, 10, 10, 8];
+    if let Some(max) = max_width {
+        let fixed = table_width(&widths) - widths[0];
+        widths[0] = widths[0].min(max.saturating_sub(fixed)).max(MIN_PROGRAM_WIDTH);
+    }
+    widths
+}
+
+/// Characters in one table line; every column carries two spaces of padding
+/// and one border
+fn table_width(widths: &[usize]) -> usize {
+    widths.iter().sum::<usize>() + 3 * widths.len() + 1
+}
+
+fn rule(glyphs: &Glyphs, corners: &[char; 3], widths: &[usize]) -> String {
+    let segments: Vec<String> = widths
+        .iter()
+        .map(|w| glyphs.horizontal.to_string().repeat(w + 2))
+        .collect();
+    format!(
+        "{}{}{}",
+        corners[0],
+        segments.join(&corners[1].to_string()),
+        corners[2]
+    )
+}
+
+/// One table row; numeric rows right-align everything after the first two columns
+fn row(glyphs: &Glyphs, widths: &[usize], cells: [String; 5], numeric: bool) -> String {
+    let mut line = String::new();
+    for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+        line.push(glyphs.vertical);
+        if numeric && i >= 2 {
+            line.push_str(&format!(" {:>w$} ", cell, w = width));
+        } else {
+            line.push_str(&format!(" {:<w$} ", cell, w = width));
+        }
+    }
+    line.push(glyphs.vertical);
+    line
+}
+
+fn truncate(text: &str, max: usize, ellipsis: char) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max.saturating_sub(1)).collect();
+    out.push(ellipsis);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::sample_result;
+
+    #[test]
+    fn widths_fit_the_longest_program_name() {
+        let mut programs = sample_result().programs;
+        // "Jito | StakeNet" is one wider than the default
+        assert_eq!(column_widths(&programs, None), [15, 11, 10, 10, 8]);
+        assert_eq!(table_width(&column_widths(&programs, None)), 70);
+
+        programs.truncate(1);
+        assert_eq!(column_widths(&programs, None)[0], PROGRAM_WIDTH);
+    }
+
+    #[test]
+    fn max_width_squeezes_only_the_program_column() {
+        let programs = sample_result().programs;
+        assert_eq!(column_widths(&programs, Some(65)), [10, 11, 10, 10, 8]);
+        assert_eq!(table_width(&column_widths(&programs, Some(65))), 65);
+        // Never below the minimum, even when the table can't fit
+        assert_eq!(column_widths(&programs, Some(40))[0], MIN_PROGRAM_WIDTH);
+        assert_eq!(column_widths(&programs, Some(200))[0], 15);
+    }
+
+    #[test]
+    fn truncate_counts_characters_and_marks_the_cut() {
+        assert_eq!(truncate("Marinade", 8, '…'), "Marinade");
+        assert_eq!(truncate("Marinade", 5, '…'), "Mari…");
+        assert_eq!(truncate("✅ Active", 4, '~'), "✅ A~");
+    }
+
+    #[test]
+    fn rows_and_rules_have_the_table_width() {
+        let widths = [7, 11, 10, 10, 8];
+        let cells = ["Jito".to_string(), "Unknown".to_string(), "0".to_string(), "800".to_string(), "+800".to_string()];
+        assert_eq!(
+            row(&ASCII, &widths, cells, true),
+            "| Jito    | Unknown     |          0 |        800 |     +800 |"
+        );
+        assert_eq!(
+            rule(&ASCII, &ASCII.top, &widths),
+            "+---------+-------------+------------+------------+----------+"
+        );
+        assert_eq!(rule(&UNICODE, &UNICODE.top, &widths).chars().count(), table_width(&widths));
+    }
+}
@@ -0,0 +1,7 @@
+//! YAML rendering, for config-management tools that prefer it over JSON
+
+use crate::types::*;
+
+pub fn render_yaml(result: &ScanResult) -> String {
+    serde_yaml::to_string(result).expect("a scan result always serializes")
+}
@@ -0,0 +1,149 @@
+//! SOL/USD price lookup used for USD projections
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+use crate::fixtures::Upstream;
+
+/// Price used when no source is reachable
+pub const FALLBACK_SOL_PRICE_USD: f64 = 200.0;
+
+/// How long a fetched price is reused before hitting the source again
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+pub(crate) const COINGECKO_URL: &str =
+    "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd";
+
+/// Pyth Hermes SOL/USD price feed
+pub(crate) const PYTH_URL: &str = "https://hermes.pyth.network/v2/updates/price/latest?ids[]=0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+/// Last fetched price, shared by everything using one [`Upstream`]
+#[derive(Debug, Default)]
+pub(crate) struct PriceCache(Mutex<Option<CachedPrice>>);
+
+#[derive(Debug)]
+struct CachedPrice {
+    source: &'static str,
+    fetched_at: Instant,
+    price: f64,
+}
+
+impl PriceCache {
+    fn get(&self, source: &PriceSource) -> Option<f64> {
+        let cached = self.0.lock().unwrap();
+        let cached = cached.as_ref()?;
+        (cached.source == source.name() && cached.fetched_at.elapsed() < CACHE_TTL).then_some(cached.price)
+    }
+
+    fn put(&self, source: &PriceSource, price: f64) {
+        *self.0.lock().unwrap() = Some(CachedPrice {
+            source: source.name(),
+            fetched_at: Instant::now(),
+            price,
+        });
+    }
+}
+
+/// Live price feeds, as named on the command line
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum PriceFeed {
+    #[cfg_attr(feature = "cli", value(name = "coingecko"))]
+    CoinGecko,
+    Pyth,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceSource {
+    CoinGecko,
+    Pyth,
+    /// Fixed price, never touches the network (air-gapped use)
+    Fixed(f64),
+}
+
+impl PriceSource {
+    /// Build a source from the CLI flags; an explicit price always wins
+    pub fn from_args(feed: PriceFeed, fixed: Option<f64>) -> Self {
+        match (fixed, feed) {
+            (Some(price), _) => PriceSource::Fixed(price),
+            (None, PriceFeed::CoinGecko) => PriceSource::CoinGecko,
+            (None, PriceFeed::Pyth) => PriceSource::Pyth,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PriceSource::CoinGecko => "coingecko",
+            PriceSource::Pyth => "pyth",
+            PriceSource::Fixed(_) => "fixed",
+        }
+    }
+}
+
+/// Current SOL price in USD, `None` when the source is unreachable (callers
+/// then use `FALLBACK_SOL_PRICE_USD` and say so)
+pub async fn sol_price_usd(source: &PriceSource, upstream: &Upstream) -> Option<f64> {
+    if let PriceSource::Fixed(price) = source {
+        return Some(*price);
+    }
+
+    // Only live prices are shared; recorded, replayed and demo fetches always run
+    if upstream.is_live() {
+        if let Some(price) = upstream.price_cache().get(source) {
+            return Some(price);
+        }
+    }
+
+    match fetch_price(source, upstream).await {
+        Ok(price) => {
+            if upstream.is_live() {
+                upstream.price_cache().put(source, price);
+            }
+            Some(price)
+        }
+        Err(e) => {
+            tracing::warn!("SOL price fetch from {} failed: {}", source.name(), e);
+            None
+        }
+    }
+}
+
+/// Fetch straight from the source, bypassing the cache and fallback
+pub async fn fetch_price(source: &PriceSource, upstream: &Upstream) -> Result<f64> {
+    match source {
+        PriceSource::CoinGecko => fetch_coingecko(upstream).await,
+        PriceSource::Pyth => fetch_pyth(upstream).await,
+        PriceSource::Fixed(price) => Ok(*price),
+    }
+}
+
+async fn fetch_json(url: &str, upstream: &Upstream) -> Result<serde_json::Value> {
+    upstream.get_json(url, upstream.request_timeout(Duration::from_secs(5))).await
+}
+
+async fn fetch_coingecko(upstream: &Upstream) -> Result<f64> {
+    fetch_json(COINGECKO_URL, upstream)
+        .await?
+        .pointer("/solana/usd")
+        .and_then(|p| p.as_f64())
+        .context("missing solana.usd in CoinGecko response")
+}
+
+async fn fetch_pyth(upstream: &Upstream) -> Result<f64> {
+    let body = fetch_json(PYTH_URL, upstream).await?;
+    let price = body
+        .pointer("/parsed/0/price")
+        .context("missing parsed price in Pyth response")?;
+
+    // Pyth reports an integer price with a decimal exponent
+    let raw: f64 = price
+        .get("price")
+        .and_then(|p| p.as_str())
+        .and_then(|p| p.parse().ok())
+        .context("invalid Pyth price")?;
+    let expo = price.get("expo").and_then(|e| e.as_i64()).unwrap_or(0);
+
+    Ok(raw * 10f64.powi(expo as i32))
+}
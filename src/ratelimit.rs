@@ -0,0 +1,163 @@
+//! Per-IP token bucket rate limiting for the API server
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Drop idle buckets once the table grows past this many clients
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_second: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// A `per_minute` of 0 never limits, like `--rate-limit 0`
+    pub fn new(per_minute: u32, burst: u32) -> Self {
+        Self {
+            per_second: per_minute as f64 / 60.0,
+            burst: burst.max(1) as f64,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for `ip`, or return how long until one is available
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        // Without a refill rate every wait would be infinite
+        if self.per_second == 0.0 {
+            return Ok(());
+        }
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() > MAX_TRACKED_CLIENTS {
+            let full_after = Duration::from_secs_f64(self.burst / self.per_second);
+            buckets.retain(|_, b| now.duration_since(b.updated) < full_after);
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.per_second))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+
+    #[test]
+    fn allows_a_burst_then_limits() {
+        let limiter = RateLimiter::new(60, 3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limiter.check_at(CLIENT, now), Ok(()));
+        }
+        assert!(limiter.check_at(CLIENT, now).is_err());
+    }
+
+    #[test]
+    fn refills_at_the_configured_rate() {
+        let limiter = RateLimiter::new(60, 1);
+        let start = Instant::now();
+        assert_eq!(limiter.check_at(CLIENT, start), Ok(()));
+        assert!(limiter.check_at(CLIENT, start + Duration::from_millis(500)).is_err());
+        assert_eq!(limiter.check_at(CLIENT, start + Duration::from_secs(1)), Ok(()));
+    }
+
+    #[test]
+    fn refill_is_capped_at_the_burst() {
+        let limiter = RateLimiter::new(60, 2);
+        let start = Instant::now();
+        assert_eq!(limiter.check_at(CLIENT, start), Ok(()));
+        let later = start + Duration::from_secs(3600);
+        assert_eq!(limiter.check_at(CLIENT, later), Ok(()));
+        assert_eq!(limiter.check_at(CLIENT, later), Ok(()));
+        assert!(limiter.check_at(CLIENT, later).is_err());
+    }
+
+    #[test]
+    fn retry_after_is_the_time_to_the_next_token() {
+        // 30/min is one token every two seconds
+        let limiter = RateLimiter::new(30, 1);
+        let start = Instant::now();
+        assert_eq!(limiter.check_at(CLIENT, start), Ok(()));
+        assert_eq!(limiter.check_at(CLIENT, start), Err(Duration::from_secs(2)));
+
+        let retry = limiter.check_at(CLIENT, start + Duration::from_millis(500)).unwrap_err();
+        assert!((retry.as_secs_f64() - 1.5).abs() < 1e-9, "{:?}", retry);
+    }
+
+    #[test]
+    fn zero_rate_never_limits() {
+        let limiter = RateLimiter::new(0, 1);
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert_eq!(limiter.check_at(CLIENT, now), Ok(()));
+        }
+    }
+
+    #[test]
+    fn clients_have_separate_buckets() {
+        let limiter = RateLimiter::new(60, 1);
+        let now = Instant::now();
+        let other = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(limiter.check_at(CLIENT, now), Ok(()));
+        assert_eq!(limiter.check_at(other, now), Ok(()));
+        assert!(limiter.check_at(CLIENT, now).is_err());
+    }
+
+    #[test]
+    fn sweeps_idle_clients_once_the_table_is_full() {
+        let limiter = RateLimiter::new(60, 5);
+        let start = Instant::now();
+        for i in 0..=MAX_TRACKED_CLIENTS as u32 {
+            let ip = IpAddr::V4(std::net::Ipv4Addr::from(i));
+            assert_eq!(limiter.check_at(ip, start), Ok(()));
+        }
+        assert_eq!(limiter.buckets.lock().unwrap().len(), MAX_TRACKED_CLIENTS + 1);
+
+        // Five tokens at one per second: every bucket is full again after 5s
+        assert_eq!(limiter.check_at(CLIENT, start + Duration::from_secs(5)), Ok(()));
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn sweep_keeps_clients_that_are_still_refilling() {
+        let limiter = RateLimiter::new(60, 5);
+        let start = Instant::now();
+        for i in 0..=MAX_TRACKED_CLIENTS as u32 {
+            let ip = IpAddr::V4(std::net::Ipv4Addr::from(i));
+            assert_eq!(limiter.check_at(ip, start), Ok(()));
+        }
+
+        assert_eq!(limiter.check_at(CLIENT, start + Duration::from_secs(4)), Ok(()));
+        assert_eq!(limiter.buckets.lock().unwrap().len(), MAX_TRACKED_CLIENTS + 2);
+    }
+}
@@ -1,17 +1,91 @@
 //! Program scanners - each scanner queries a specific delegation program
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::Utc;
 use serde_json::json;
 
+use crate::fixtures::{Mode, Upstream};
+use crate::price::{self, PriceSource};
 use crate::types::*;
 
-const SOL_PRICE_USD: f64 = 200.0; // TODO: Fetch live price
+/// Program slugs accepted by `--program`
+pub const PROGRAM_NAMES: [&str; 5] = ["marinade", "jito", "blaze", "sanctum", "sfdp"];
+
+/// Marinade validator set, including scores and delegated stake
+pub const MARINADE_VALIDATORS_URL: &str = "https://validators-api.marinade.finance/validators";
+
+/// Programs whose scanners have no live data source yet and only estimate
+pub const ESTIMATED_PROGRAMS: [&str; 4] = ["jito", "blaze", "sanctum", "sfdp"];
+
+/// `scan` exit code when some programs could not be fetched
+pub const PARTIAL_EXIT_CODE: i32 = 4;
+
+/// Upstream program data, fetched once and shared by every validator in a scan
+#[derive(Debug)]
+struct ProgramData {
+    /// Marinade validator set, `None` when not requested or the fetch failed
+    marinade: Option<Vec<serde_json::Value>>,
+    /// Why the Marinade fetch failed
+    marinade_error: Option<String>,
+}
 
 /// Scan a validator across all (or specific) programs
-pub async fn scan_validator(validator: &str, program: Option<&str>) -> Result<ScanResult> {
+pub async fn scan_validator(
+    validator: &str,
+    program: Option<&str>,
+    price_source: &PriceSource,
+    upstream: &Upstream,
+) -> Result<ScanResult> {
+    let mut results = scan_validators(&[validator.to_string()], program, price_source, upstream).await?;
+    Ok(results.remove(0))
+}
+
+/// Scan several validators, fetching each program's data only once
+pub async fn scan_validators(
+    validators: &[String],
+    program: Option<&str>,
+    price_source: &PriceSource,
+    upstream: &Upstream,
+) -> Result<Vec<ScanResult>> {
+    let wants = |name: &str| program.is_none_or(|p| p == name);
+    
+    let (marinade, sol_price_usd) = tokio::join!(
+        async {
+            if wants("marinade") {
+                fetch_marinade(upstream).await.map(Some)
+            } else {
+                Ok(None)
+            }
+        },
+        price::sol_price_usd(price_source, upstream),
+    );
+    // A failed fetch degrades that program to unknown rather than failing the scan
+    let data = match marinade {
+        Ok(marinade) => ProgramData { marinade, marinade_error: None },
+        Err(e) => {
+            tracing::warn!("Marinade fetch failed: {:#}", e);
+            ProgramData { marinade: None, marinade_error: Some(format!("{:#}", e)) }
+        }
+    };
+    
+    let demo = upstream.mode() == Some(&Mode::Demo);
+    let mut results = Vec::with_capacity(validators.len());
+    for validator in validators {
+        let mut result = scan_with(validator, program, &data, sol_price_usd).await?;
+        result.demo = demo;
+        results.push(result);
+    }
+    Ok(results)
+}
+
+async fn scan_with(
+    validator: &str,
+    program: Option<&str>,
+    data: &ProgramData,
+    sol_price_usd: Option<f64>,
+) -> Result<ScanResult> {
     let programs = match program {
-        Some("marinade") => vec![scan_marinade(validator).await?],
+        Some("marinade") => vec![scan_marinade(validator, data).await?],
         Some("jito") => vec![scan_jito(validator).await?],
         Some("blaze") => vec![scan_blaze(validator).await?],
         Some("sanctum") => vec![scan_sanctum(validator).await?],
@@ -20,7 +94,7 @@ pub async fn scan_validator(validator: &str, program: Option<&str>) -> Result<Sc
         None => {
             // Scan all programs concurrently
             let (marinade, jito, blaze, sanctum, sfdp) = tokio::join!(
-                scan_marinade(validator),
+                scan_marinade(validator, data),
                 scan_jito(validator),
                 scan_blaze(validator),
                 scan_sanctum(validator),
@@ -37,12 +111,14 @@ pub async fn scan_validator(validator: &str, program: Option<&str>) -> Result<Sc
     };
     
     // Calculate summary
+    let price_is_fallback = sol_price_usd.is_none();
+    let sol_price_usd = sol_price_usd.unwrap_or(price::FALLBACK_SOL_PRICE_USD);
     let total_current: f64 = programs.iter().map(|p| p.current_stake_sol).sum();
     let total_potential: f64 = programs.iter().map(|p| p.potential_stake_sol).sum();
     let missed = total_potential - total_current;
     
     // Generate action items for programs with gaps
-    let action_items: Vec<ActionItem> = programs
+    let mut action_items: Vec<ActionItem> = programs
         .iter()
         .filter(|p| p.gap_sol > 0.0 && p.status != RegistrationStatus::Ineligible)
         .map(|p| ActionItem {
@@ -60,37 +136,72 @@ pub async fn scan_validator(validator: &str, program: Option<&str>) -> Result<Sc
             },
         })
         .collect();
+    action_items.sort_by(|a, b| {
+        a.difficulty
+            .cmp(&b.difficulty)
+            .then(b.potential_gain_sol.total_cmp(&a.potential_gain_sol))
+    });
+    let milestones = milestones(&action_items);
     
     Ok(ScanResult {
         validator: validator.to_string(),
         scanned_at: Utc::now(),
         programs,
+        demo: false,
         summary: ScanSummary {
             total_current_sol: total_current,
             total_potential_sol: total_potential,
             missed_revenue_sol: missed,
-            missed_revenue_usd: missed * SOL_PRICE_USD,
+            missed_revenue_usd: missed * sol_price_usd,
+            sol_price_usd,
+            price_is_fallback,
             action_items,
+            milestones,
         },
     })
 }
 
-/// Scan Marinade Finance
-async fn scan_marinade(validator: &str) -> Result<ProgramStatus> {
-    let client = reqwest::Client::new();
-    
-    let resp = client
-        .get("https://validators-api.marinade.finance/validators")
-        .timeout(std::time::Duration::from_secs(10))
-        .send()
-        .await?;
-    
-    if !resp.status().is_success() {
-        return Ok(ProgramStatus::new("marinade", "Marinade")
-            .with_status(RegistrationStatus::Unknown));
+/// Group sorted action items into one milestone per difficulty
+fn milestones(action_items: &[ActionItem]) -> Vec<Milestone> {
+    let mut milestones: Vec<Milestone> = Vec::new();
+    let mut cumulative = 0.0;
+    for item in action_items {
+        cumulative += item.potential_gain_sol;
+        match milestones.last_mut() {
+            Some(m) if m.difficulty == item.difficulty => {
+                m.programs.push(item.program.clone());
+                m.gain_sol += item.potential_gain_sol;
+                m.cumulative_gain_sol = cumulative;
+            }
+            _ => milestones.push(Milestone {
+                difficulty: item.difficulty,
+                programs: vec![item.program.clone()],
+                gain_sol: item.potential_gain_sol,
+                cumulative_gain_sol: cumulative,
+            }),
+        }
     }
-    
-    let validators: Vec<serde_json::Value> = resp.json().await?;
+    milestones
+}
+
+/// Fetch the Marinade validator set
+async fn fetch_marinade(upstream: &Upstream) -> Result<Vec<serde_json::Value>> {
+    let timeout = upstream.request_timeout(std::time::Duration::from_secs(10));
+    upstream
+        .get_json(MARINADE_VALIDATORS_URL, timeout)
+        .await
+        .context("fetching Marinade validators")
+}
+
+/// Scan Marinade Finance
+async fn scan_marinade(validator: &str, data: &ProgramData) -> Result<ProgramStatus> {
+    let Some(validators) = &data.marinade else {
+        let status = ProgramStatus::new("marinade", "Marinade").with_status(RegistrationStatus::Unknown);
+        return Ok(match &data.marinade_error {
+            Some(error) => status.with_fetch_error(error),
+            None => status,
+        });
+    };
     
     // Find our validator
     let found = validators.iter().find(|v| {
@@ -106,6 +217,17 @@ async fn scan_marinade(validator: &str) -> Result<ProgramStatus> {
             let stake = v.get("marinade_stake").and_then(|s| s.as_f64()).unwrap_or(0.0);
             let eligible = v.get("eligible_stake_algo").and_then(|s| s.as_bool()).unwrap_or(false);
             
+            // Where the score sits among validators Marinade would delegate to
+            let cohort: Vec<f64> = validators
+                .iter()
+                .filter(|v| v.get("eligible_stake_algo").and_then(|e| e.as_bool()).unwrap_or(false))
+                .filter_map(|v| v.get("score").and_then(|s| s.as_f64()))
+                .collect();
+            let (score_percentile, cohort_median_score) = match cohort_position(score, cohort) {
+                Some((percentile, median)) => (Some(percentile), Some(median)),
+                None => (None, None),
+            };
+            
             Ok(ProgramStatus::new("marinade", "Marinade")
                 .with_status(if stake > 0.0 { RegistrationStatus::Active } 
                              else if eligible { RegistrationStatus::Eligible }
@@ -114,6 +236,8 @@ async fn scan_marinade(validator: &str) -> Result<ProgramStatus> {
                 .with_details(json!({
                     "score": score,
                     "eligible": eligible,
+                    "score_percentile": score_percentile,
+                    "cohort_median_score": cohort_median_score,
                 })))
         }
         None => {
@@ -129,8 +253,27 @@ async fn scan_marinade(validator: &str) -> Result<ProgramStatus> {
     }
 }
 
+/// Percentile of `value` within `cohort` (share at or below it) and the cohort median
+fn cohort_position(value: f64, mut cohort: Vec<f64>) -> Option<(f64, f64)> {
+    if cohort.is_empty() {
+        return None;
+    }
+    cohort.sort_by(f64::total_cmp);
+    
+    let at_or_below = cohort.iter().filter(|&&c| c <= value).count();
+    let percentile = at_or_below as f64 / cohort.len() as f64 * 100.0;
+    
+    let mid = cohort.len() / 2;
+    let median = if cohort.len().is_multiple_of(2) {
+        (cohort[mid - 1] + cohort[mid]) / 2.0
+    } else {
+        cohort[mid]
+    };
+    Some((percentile, median))
+}
+
 /// Scan Jito StakeNet
-async fn scan_jito(validator: &str) -> Result<ProgramStatus> {
+async fn scan_jito(_validator: &str) -> Result<ProgramStatus> {
     // TODO: Implement actual Jito API call
     // For now, return a placeholder that indicates checking is needed
     
@@ -145,7 +288,7 @@ async fn scan_jito(validator: &str) -> Result<ProgramStatus> {
 }
 
 /// Scan SolBlaze
-async fn scan_blaze(validator: &str) -> Result<ProgramStatus> {
+async fn scan_blaze(_validator: &str) -> Result<ProgramStatus> {
     // TODO: Implement actual Blaze API call
     
     Ok(ProgramStatus::new("blaze", "SolBlaze")
@@ -158,7 +301,7 @@ async fn scan_blaze(validator: &str) -> Result<ProgramStatus> {
 }
 
 /// Scan Sanctum Gauge
-async fn scan_sanctum(validator: &str) -> Result<ProgramStatus> {
+async fn scan_sanctum(_validator: &str) -> Result<ProgramStatus> {
     // TODO: Implement Sanctum API/on-chain check
     
     Ok(ProgramStatus::new("sanctum", "Sanctum Gauge")
@@ -171,7 +314,7 @@ async fn scan_sanctum(validator: &str) -> Result<ProgramStatus> {
 }
 
 /// Scan Solana Foundation Delegation Program
-async fn scan_sfdp(validator: &str) -> Result<ProgramStatus> {
+async fn scan_sfdp(_validator: &str) -> Result<ProgramStatus> {
     // TODO: Check on-chain SFDP status
     
     Ok(ProgramStatus::new("sfdp", "SFDP")
@@ -182,3 +325,50 @@ async fn scan_sfdp(validator: &str) -> Result<ProgramStatus> {
             "note": "Check Solana Foundation for delegation status"
         })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DEMO_VALIDATOR;
+
+    #[tokio::test]
+    async fn scans_the_demo_validator() {
+        let upstream = Upstream::new(Mode::Demo).unwrap();
+        let result = scan_validator(DEMO_VALIDATOR, None, &PriceSource::CoinGecko, &upstream)
+            .await
+            .unwrap();
+
+        assert!(result.demo);
+        let marinade = &result.programs[0];
+        assert_eq!(marinade.name, "marinade");
+        assert_eq!(marinade.status, RegistrationStatus::Active);
+        assert_eq!(marinade.fetch_error, None);
+        assert_eq!(marinade.details["score_percentile"], 100.0);
+        assert_eq!(marinade.details["cohort_median_score"], 0.875);
+
+        assert_eq!(result.summary.sol_price_usd, 150.0);
+        assert!(!result.is_partial());
+
+        let milestones: Vec<_> = result
+            .summary
+            .milestones
+            .iter()
+            .map(|m| (m.difficulty, m.programs.join(","), m.gain_sol, m.cumulative_gain_sol))
+            .collect();
+        assert_eq!(
+            milestones,
+            [
+                (Difficulty::Easy, "jito".to_string(), 800.0, 800.0),
+                (Difficulty::Medium, "sanctum,blaze".to_string(), 1400.0, 2200.0),
+                (Difficulty::Hard, "sfdp".to_string(), 25000.0, 27200.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn cohort_position_of_odd_and_even_cohorts() {
+        assert_eq!(cohort_position(0.5, vec![0.75, 0.25, 0.5, 1.0]), Some((50.0, 0.625)));
+        assert_eq!(cohort_position(0.25, vec![1.0, 0.25, 0.5, 0.0, 0.75]), Some((40.0, 0.5)));
+        assert_eq!(cohort_position(0.5, vec![]), None);
+    }
+}
@@ -0,0 +1,50 @@
+//! JSON Schema for the oracle's output contract, for validation and codegen
+
+use schemars::{schema::RootSchema, schema_for};
+
+#[cfg(feature = "server")]
+use crate::api::{BulkScanRequest, ErrorResponse, HealthResponse, ProgramInfo};
+use crate::types::*;
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SchemaType {
+    /// `scan --output json` and GET /api/scan
+    ScanResult,
+    /// One entry of `ScanResult.programs`
+    ProgramStatus,
+    /// POST /api/scan/bulk request body
+    #[cfg(feature = "server")]
+    BulkScanRequest,
+    /// POST /api/scan/bulk response
+    #[cfg(feature = "server")]
+    BulkScanResponse,
+    /// GET /api/health
+    #[cfg(feature = "server")]
+    Health,
+    /// GET /api/programs
+    #[cfg(feature = "server")]
+    Programs,
+    /// Error body returned by every API endpoint
+    #[cfg(feature = "server")]
+    Error,
+}
+
+impl SchemaType {
+    pub fn schema(self) -> RootSchema {
+        match self {
+            SchemaType::ScanResult => schema_for!(ScanResult),
+            SchemaType::ProgramStatus => schema_for!(ProgramStatus),
+            #[cfg(feature = "server")]
+            SchemaType::BulkScanRequest => schema_for!(BulkScanRequest),
+            #[cfg(feature = "server")]
+            SchemaType::BulkScanResponse => schema_for!(Vec<ScanResult>),
+            #[cfg(feature = "server")]
+            SchemaType::Health => schema_for!(HealthResponse),
+            #[cfg(feature = "server")]
+            SchemaType::Programs => schema_for!(Vec<ProgramInfo>),
+            #[cfg(feature = "server")]
+            SchemaType::Error => schema_for!(ErrorResponse),
+        }
+    }
+}
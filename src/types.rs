@@ -1,7 +1,9 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct ProgramStatus {
     pub name: String,
     pub display_name: String,
@@ -10,10 +12,15 @@ pub struct ProgramStatus {
     pub potential_stake_sol: f64,
     pub gap_sol: f64,
     pub registration_url: Option<String>,
+    #[cfg_attr(feature = "server", schema(value_type = Object))]
     pub details: serde_json::Value,
+    /// Why the program's data could not be fetched; the status is then unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetch_error: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum RegistrationStatus {
     Active,
@@ -23,24 +30,39 @@ pub enum RegistrationStatus {
     Unknown,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct ScanResult {
     pub validator: String,
     pub scanned_at: DateTime<Utc>,
     pub programs: Vec<ProgramStatus>,
     pub summary: ScanSummary,
+    /// Built from the built-in sample data (`--demo`), not from live upstreams
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub demo: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct ScanSummary {
     pub total_current_sol: f64,
     pub total_potential_sol: f64,
     pub missed_revenue_sol: f64,
     pub missed_revenue_usd: f64,
+    /// 0 in results saved before the price was included
+    #[serde(default)]
+    pub sol_price_usd: f64,
+    /// The price feed was unreachable and `sol_price_usd` is the fixed fallback
+    #[serde(default)]
+    pub price_is_fallback: bool,
+    /// Easiest first, then largest gain
     pub action_items: Vec<ActionItem>,
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct ActionItem {
     pub program: String,
     pub action: String,
@@ -49,7 +71,20 @@ pub struct ActionItem {
     pub difficulty: Difficulty,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Action items of one difficulty, taken together after all easier ones
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct Milestone {
+    pub difficulty: Difficulty,
+    pub programs: Vec<String>,
+    pub gain_sol: f64,
+    /// Gain from this and every easier milestone
+    pub cumulative_gain_sol: f64,
+}
+
+/// Ordered easiest first
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Difficulty {
     Easy,
@@ -68,6 +103,7 @@ impl ProgramStatus {
             gap_sol: 0.0,
             registration_url: None,
             details: serde_json::Value::Null,
+            fetch_error: None,
         }
     }
     
@@ -92,4 +128,42 @@ impl ProgramStatus {
         self.details = details;
         self
     }
+    
+    pub fn with_fetch_error(mut self, error: &str) -> Self {
+        self.fetch_error = Some(error.to_string());
+        self
+    }
+}
+
+impl ScanResult {
+    /// True when some program's data or the SOL price could not be fetched
+    pub fn is_partial(&self) -> bool {
+        self.summary.price_is_fallback || self.programs.iter().any(|p| p.fetch_error.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_results_saved_before_price_and_milestones() {
+        let saved = r#"{
+            "validator": "Va1idator1111111111111111111111111111111111",
+            "scanned_at": "2026-01-02T03:04:05Z",
+            "programs": [],
+            "summary": {
+                "total_current_sol": 0.0,
+                "total_potential_sol": 0.0,
+                "missed_revenue_sol": 0.0,
+                "missed_revenue_usd": 0.0,
+                "action_items": []
+            }
+        }"#;
+        let result: ScanResult = serde_json::from_str(saved).unwrap();
+        assert_eq!(result.summary.sol_price_usd, 0.0);
+        assert!(!result.summary.price_is_fallback);
+        assert!(result.summary.milestones.is_empty());
+        assert!(!result.demo);
+    }
 }